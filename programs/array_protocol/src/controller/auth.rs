@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{User, DELEGATE_PERMISSION_DRIFT, DELEGATE_PERMISSION_KAMINO};
+
+/// The kind of operation a signer is attempting against a `User` account.
+///
+/// Policy:
+/// - the `authority` may perform every action.
+/// - the `delegate` (keeper) may only move funds between the user's idle vault and the
+///   integrated protocols it was granted in `User.delegate_permissions`, and create the protocol
+///   accounts needed to do so. It can never move tokens to or from a wallet, nor manage the
+///   user's vaults or delegate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserAction {
+    /// Create or manage the user's own accounts (`init_user_token_vault`, `set_delegate`).
+    ManageAccount,
+    /// Move tokens between a wallet and the user's vault (`deposit_spl`, `withdraw_spl`).
+    Transfer,
+    /// Create Drift accounts or move funds between the vault and Drift.
+    Drift,
+    /// Create Kamino accounts or move funds between the vault and Kamino.
+    Kamino,
+}
+
+impl UserAction {
+    /// Permission bits the delegate needs for this action, or `None` if it is authority-only.
+    pub fn delegate_permission(&self) -> Option<u8> {
+        match self {
+            UserAction::ManageAccount | UserAction::Transfer => None,
+            UserAction::Drift => Some(DELEGATE_PERMISSION_DRIFT),
+            UserAction::Kamino => Some(DELEGATE_PERMISSION_KAMINO),
+        }
+    }
+}
+
+/// Returns whether `signer` is allowed to perform `action` on `user`.
+pub fn is_authorized(user: &User, signer: &Pubkey, action: UserAction) -> bool {
+    if *signer == user.authority {
+        return true;
+    }
+
+    if user.delegate == Pubkey::default() || *signer != user.delegate {
+        return false;
+    }
+
+    match action.delegate_permission() {
+        Some(permission) => user.delegate_permissions & permission == permission,
+        None => false,
+    }
+}
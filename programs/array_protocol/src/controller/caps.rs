@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::controller::VaultError;
+use crate::state::{SupportedTokenVault, UserTokenVault};
+
+/// Fails if depositing `amount` more would take the vault or the user past its cap. A cap of 0
+/// means unlimited.
+pub fn ensure_within_deposit_caps(
+    vault: &SupportedTokenVault,
+    user_token_vault: &UserTokenVault,
+    amount: u64,
+) -> Result<()> {
+    if vault.max_total_deposits != 0 {
+        let total = vault
+            .balance
+            .checked_add(amount as u128)
+            .ok_or(VaultError::Overflow)?;
+        require!(
+            total <= vault.max_total_deposits as u128,
+            VaultError::VaultDepositCapExceeded
+        );
+    }
+    if vault.max_user_deposits != 0 {
+        let total = user_token_vault
+            .deposited_amount
+            .checked_add(amount as u128)
+            .ok_or(VaultError::Overflow)?;
+        require!(
+            total <= vault.max_user_deposits as u128,
+            VaultError::UserDepositCapExceeded
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+
+use crate::controller::VaultError;
+use crate::ids;
+use crate::state::{User, DELEGATE_PERMISSION_DRIFT};
+
+/// Offset of `sub_account_id` in the Drift `User` account, which is too large to deserialize.
+pub const DRIFT_USER_SUB_ACCOUNT_ID_OFFSET: usize = 4346;
+
+/// Drift's global `State` account.
+pub fn drift_state_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"drift_state"], &ids::drift::ID).0
+}
+
+/// Drift's signer PDA, authority of the spot market vaults.
+pub fn drift_signer_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"drift_signer"], &ids::drift::ID).0
+}
+
+/// Drift `User` account of `authority`'s `sub_account_id`.
+pub fn drift_user_address(authority: &Pubkey, sub_account_id: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"user", authority.as_ref(), &sub_account_id.to_le_bytes()],
+        &ids::drift::ID,
+    )
+    .0
+}
+
+/// Drift `UserStats` account of `authority`, shared by all its sub-accounts.
+pub fn drift_user_stats_address(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"user_stats", authority.as_ref()], &ids::drift::ID).0
+}
+
+/// Token account holding the deposits of Drift spot market `market_index`.
+pub fn drift_spot_market_vault_address(market_index: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"spot_market_vault", &market_index.to_le_bytes()],
+        &ids::drift::ID,
+    )
+    .0
+}
+
+/// Drift `SpotMarket` account of `market_index`.
+pub fn drift_spot_market_address(market_index: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"spot_market", &market_index.to_le_bytes()],
+        &ids::drift::ID,
+    )
+    .0
+}
+
+/// Token account holding the insurance fund of Drift spot market `market_index`.
+pub fn drift_insurance_fund_vault_address(market_index: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"insurance_fund_vault", &market_index.to_le_bytes()],
+        &ids::drift::ID,
+    )
+    .0
+}
+
+/// Drift `InsuranceFundStake` account of `authority` in the insurance fund of `market_index`.
+pub fn drift_insurance_fund_stake_address(authority: &Pubkey, market_index: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"insurance_fund_stake",
+            authority.as_ref(),
+            &market_index.to_le_bytes(),
+        ],
+        &ids::drift::ID,
+    )
+    .0
+}
+
+/// Fails unless the Drift accounts are the ones Drift derives for `authority`'s
+/// `sub_account_id` and spot market `market_index`. For instructions whose Drift accounts are
+/// optional and can't be checked by constraints.
+pub fn ensure_drift_accounts(
+    authority: &Pubkey,
+    sub_account_id: u16,
+    market_index: u16,
+    state: &Pubkey,
+    user: &Pubkey,
+    user_stats: &Pubkey,
+    spot_market_vault: &Pubkey,
+    drift_signer: Option<&Pubkey>,
+) -> Result<()> {
+    require_keys_eq!(
+        *state,
+        drift_state_address(),
+        VaultError::InvalidDriftAccount
+    );
+    require_keys_eq!(
+        *user,
+        drift_user_address(authority, sub_account_id),
+        VaultError::InvalidDriftAccount
+    );
+    require_keys_eq!(
+        *user_stats,
+        drift_user_stats_address(authority),
+        VaultError::InvalidDriftAccount
+    );
+    require_keys_eq!(
+        *spot_market_vault,
+        drift_spot_market_vault_address(market_index),
+        VaultError::DriftMarketMismatch
+    );
+    if let Some(drift_signer) = drift_signer {
+        require_keys_eq!(
+            *drift_signer,
+            drift_signer_address(),
+            VaultError::InvalidDriftAccount
+        );
+    }
+    Ok(())
+}
+
+/// Reads the sub-account id from the data of a Drift `User` account.
+pub fn drift_user_sub_account_id(data: &[u8]) -> Result<u16> {
+    let bytes = data
+        .get(DRIFT_USER_SUB_ACCOUNT_ID_OFFSET..DRIFT_USER_SUB_ACCOUNT_ID_OFFSET + 2)
+        .ok_or(VaultError::InvalidProtocolAccount)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// The delegate `user`'s Drift accounts should carry: the Array delegate if it may act on Drift,
+/// none otherwise.
+pub fn drift_delegate(user: &User) -> Pubkey {
+    if user.delegate_permissions & DELEGATE_PERMISSION_DRIFT != 0 {
+        user.delegate
+    } else {
+        Pubkey::default()
+    }
+}
@@ -8,7 +8,7 @@ pub enum VaultError {
     #[msg("Underflow occurred")]
     Underflow,
 
-    #[msg("No free position slot found!")]
+    #[msg("No free position slot found, add more with add_user_positions")]
     NoPositionSlot,
 
     #[msg("Invalid vault index for this position")]
@@ -16,4 +16,115 @@ pub enum VaultError {
 
     #[msg("Unauthorized user")]
     UnauthorizedUser,
+
+    #[msg("Invalid delegate permissions")]
+    InvalidDelegatePermissions,
+
+    #[msg("Accounts required by the selected protocol are missing")]
+    MissingProtocolAccounts,
+
+    #[msg("Invalid rebalance source or destination")]
+    InvalidRebalance,
+
+    #[msg("Amount is too small to mint any shares")]
+    ZeroShares,
+
+    #[msg("Not enough shares for this withdrawal")]
+    InsufficientShares,
+
+    #[msg("No position found for this vault and protocol")]
+    PositionNotFound,
+
+    #[msg("Protocol account does not belong to this user or position")]
+    InvalidProtocolAccount,
+
+    #[msg("Fee rate exceeds the hard cap")]
+    FeeTooHigh,
+
+    #[msg("Treasury has not been set")]
+    TreasuryNotSet,
+
+    #[msg("Signer is not the program admin")]
+    UnauthorizedAdmin,
+
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+
+    #[msg("This operation is paused")]
+    Paused,
+
+    #[msg("This operation is paused for the vault")]
+    VaultPaused,
+
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
+
+    #[msg("Deposit exceeds the vault's total deposit cap")]
+    VaultDepositCapExceeded,
+
+    #[msg("Deposit exceeds the vault's per-user deposit cap")]
+    UserDepositCapExceeded,
+
+    #[msg("Market is not registered for this vault")]
+    MarketNotRegistered,
+
+    #[msg("Market is already registered for this vault")]
+    MarketAlreadyRegistered,
+
+    #[msg("Protocol registry is full")]
+    RegistryFull,
+
+    #[msg("User token vault still holds tokens or shares")]
+    VaultNotEmpty,
+
+    #[msg("User still has an open position")]
+    PositionOpen,
+
+    #[msg("Too many position slots")]
+    TooManyPositions,
+
+    #[msg("User account is not in the legacy layout")]
+    NotLegacyUser,
+
+    #[msg("Mint has an unsupported Token-2022 extension")]
+    UnsupportedMintExtension,
+
+    #[msg("Withdrawal delay out of range")]
+    InvalidWithdrawalDelay,
+
+    #[msg("Withdrawals from this vault must be queued with request_withdraw")]
+    WithdrawalDelayActive,
+
+    #[msg("Withdrawal request is not executable yet")]
+    WithdrawalNotReady,
+
+    #[msg("A withdrawal request is still pending for this vault")]
+    WithdrawalRequestPending,
+
+    #[msg("Vault mint is not wrapped SOL")]
+    NotNativeMint,
+
+    #[msg("Drift account does not match the address derived for this user")]
+    InvalidDriftAccount,
+
+    #[msg("Spot market vault does not belong to the requested Drift market")]
+    DriftMarketMismatch,
+
+    #[msg("Source and destination sub-accounts are the same")]
+    SameSubAccount,
+
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+
+    #[msg("Account already has the current layout")]
+    AccountAlreadyResized,
+
+    #[msg("Kamino account does not match the address derived for this user")]
+    InvalidKaminoAccount,
+
+    #[msg("A user vault is still open, close it with close_user_token_vault first")]
+    UserVaultOpen,
+
+    #[msg("Amount exceeds the position's principal and synced value")]
+    ExceedsPositionValue,
 }
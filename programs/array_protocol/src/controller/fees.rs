@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+
+use crate::controller::{assets_to_shares, mul_div, shares_to_assets, VaultError};
+use crate::state::{SupportedTokenVault, UserTokenVault};
+
+pub const BPS_DENOMINATOR: u128 = 10_000;
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+/// Moves `fee` of the user's value out of the share pool and into `accrued_fees`. The tokens stay
+/// in the user vault token account until `collect_fees` sends them to the treasury.
+fn charge_fee(
+    vault: &mut SupportedTokenVault,
+    user_token_vault: &mut UserTokenVault,
+    fee: u64,
+) -> Result<()> {
+    vault.accrued_fees = vault
+        .accrued_fees
+        .checked_add(fee)
+        .ok_or(VaultError::Overflow)?;
+    user_token_vault.accrued_fees = user_token_vault
+        .accrued_fees
+        .checked_add(fee)
+        .ok_or(VaultError::Overflow)?;
+    Ok(())
+}
+
+/// Charges the management fee on the user's value for the time elapsed since the last accrual,
+/// by burning the matching shares. Returns the fee charged.
+pub fn accrue_management_fee(
+    vault: &mut SupportedTokenVault,
+    user_token_vault: &mut UserTokenVault,
+    now: i64,
+) -> Result<u64> {
+    let last = user_token_vault.last_fee_accrual_ts;
+    user_token_vault.last_fee_accrual_ts = now;
+
+    if last == 0 || now <= last || vault.management_fee_bps == 0 || user_token_vault.shares == 0 {
+        return Ok(0);
+    }
+
+    let value = shares_to_assets(user_token_vault, user_token_vault.shares)?;
+    let fee = mul_div(
+        value as u128,
+        vault.management_fee_bps as u128 * (now - last) as u128,
+        BPS_DENOMINATOR * SECONDS_PER_YEAR,
+        false,
+    )?;
+    let fee = u64::try_from(fee)
+        .map_err(|_| VaultError::Overflow)?
+        .min(value);
+
+    // Round the burned shares down so the user is never charged more than the fee
+    let shares = assets_to_shares(user_token_vault, fee)?.min(user_token_vault.shares);
+    if shares == 0 {
+        return Ok(0);
+    }
+
+    vault.balance = vault
+        .balance
+        .checked_sub(fee as u128)
+        .ok_or(VaultError::Underflow)?;
+    vault.total_shares = vault
+        .total_shares
+        .checked_sub(shares)
+        .ok_or(VaultError::Underflow)?;
+    user_token_vault.shares = user_token_vault
+        .shares
+        .checked_sub(shares)
+        .ok_or(VaultError::Underflow)?;
+    user_token_vault.balance = user_token_vault
+        .balance
+        .checked_sub(fee)
+        .ok_or(VaultError::Underflow)?;
+
+    charge_fee(vault, user_token_vault, fee)?;
+    Ok(fee)
+}
+
+/// Charges the performance fee on the yield realized by withdrawing `amount` against `principal`.
+/// The fee is kept back from the withdrawal, returns the fee charged.
+pub fn charge_performance_fee(
+    vault: &mut SupportedTokenVault,
+    user_token_vault: &mut UserTokenVault,
+    amount: u64,
+    principal: u128,
+) -> Result<u64> {
+    let realized_yield = (amount as u128).saturating_sub(principal);
+    let fee = mul_div(
+        realized_yield,
+        vault.performance_fee_bps as u128,
+        BPS_DENOMINATOR,
+        false,
+    )?;
+    let fee = u64::try_from(fee).map_err(|_| VaultError::Overflow)?;
+    if fee == 0 {
+        return Ok(0);
+    }
+
+    charge_fee(vault, user_token_vault, fee)?;
+    Ok(fee)
+}
+
+/// Clears up to `available` of the user's accrued fees once they have been paid to the treasury.
+/// Returns the amount to transfer.
+pub fn settle_fees(
+    vault: &mut SupportedTokenVault,
+    user_token_vault: &mut UserTokenVault,
+    available: u64,
+) -> Result<u64> {
+    let amount = user_token_vault.accrued_fees.min(available);
+
+    user_token_vault.accrued_fees -= amount;
+    vault.accrued_fees = vault
+        .accrued_fees
+        .checked_sub(amount)
+        .ok_or(VaultError::Underflow)?;
+    Ok(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::{book_position_value, deposit_shares, withdraw_shares};
+
+    #[test]
+    fn performance_fee_is_charged_on_booked_yield() {
+        let mut vault = SupportedTokenVault {
+            performance_fee_bps: 1_000,
+            ..Default::default()
+        };
+        let mut user = UserTokenVault::default();
+        deposit_shares(&mut vault, &mut user, 1_000).unwrap();
+        book_position_value(&mut vault, &mut user, 1_000, 1_200).unwrap();
+
+        let (_, principal) = withdraw_shares(&mut vault, &mut user, 1_200).unwrap();
+        assert_eq!(principal, 1_000);
+
+        let fee = charge_performance_fee(&mut vault, &mut user, 1_200, principal).unwrap();
+        assert_eq!(fee, 20);
+        assert_eq!(user.accrued_fees, 20);
+        assert_eq!(vault.accrued_fees, 20);
+    }
+
+    #[test]
+    fn performance_fee_is_not_charged_on_principal() {
+        let mut vault = SupportedTokenVault {
+            performance_fee_bps: 1_000,
+            ..Default::default()
+        };
+        let mut user = UserTokenVault::default();
+        deposit_shares(&mut vault, &mut user, 1_000).unwrap();
+
+        let (_, principal) = withdraw_shares(&mut vault, &mut user, 1_000).unwrap();
+        let fee = charge_performance_fee(&mut vault, &mut user, 1_000, principal).unwrap();
+        assert_eq!(fee, 0);
+    }
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::ids;
+use crate::klend;
+
+/// Kamino tag for a plain lending obligation.
+pub const KAMINO_VANILLA_OBLIGATION_TAG: u8 = 0;
+
+/// klend `UserMetadata` account of `owner`.
+pub fn kamino_user_metadata_address(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"user_meta", owner.as_ref()], &klend::ID).0
+}
+
+/// klend vanilla obligation `obligation_id` of `owner` in `lending_market`. Vanilla obligations
+/// use the default pubkey for both seed accounts.
+pub fn kamino_obligation_address(
+    owner: &Pubkey,
+    lending_market: &Pubkey,
+    obligation_id: u8,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            &[KAMINO_VANILLA_OBLIGATION_TAG],
+            &[obligation_id],
+            owner.as_ref(),
+            lending_market.as_ref(),
+            Pubkey::default().as_ref(),
+            Pubkey::default().as_ref(),
+        ],
+        &klend::ID,
+    )
+    .0
+}
+
+/// klend authority of `lending_market`, owner of its reserves' supplies.
+pub fn kamino_lending_market_authority_address(lending_market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"lma", lending_market.as_ref()], &klend::ID).0
+}
+
+/// Kamino farms user state of `obligation` in the reserve farm `farm_state`.
+pub fn kamino_obligation_farm_address(farm_state: &Pubkey, obligation: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"user", farm_state.as_ref(), obligation.as_ref()],
+        &ids::kamino_farms::ID,
+    )
+    .0
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::default_account_state::DefaultAccountState;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHook;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::{AccountState, Mint};
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+use anchor_spl::{token::spl_token, token_2022::spl_token_2022};
+
+use super::VaultError;
+
+/// Rejects Token-2022 mints carrying extensions the vaults can't work with. Transfer hooks would
+/// need extra accounts on every transfer, non-transferable mints can't move at all, and a
+/// frozen default state would lock the user vault accounts. Legacy SPL mints are always
+/// supported.
+pub fn ensure_supported_mint(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != TOKEN_2022_PROGRAM_ID {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<Mint>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        let supported = match extension {
+            ExtensionType::TransferFeeConfig
+            | ExtensionType::MintCloseAuthority
+            | ExtensionType::InterestBearingConfig
+            | ExtensionType::PermanentDelegate
+            | ExtensionType::MetadataPointer
+            | ExtensionType::TokenMetadata
+            | ExtensionType::GroupPointer
+            | ExtensionType::TokenGroup
+            | ExtensionType::GroupMemberPointer
+            | ExtensionType::TokenGroupMember
+            | ExtensionType::ConfidentialTransferMint
+            | ExtensionType::ConfidentialTransferFeeConfig => true,
+            ExtensionType::TransferHook => {
+                Option::<Pubkey>::from(state.get_extension::<TransferHook>()?.program_id).is_none()
+            }
+            ExtensionType::DefaultAccountState => {
+                state.get_extension::<DefaultAccountState>()?.state
+                    == AccountState::Initialized as u8
+            }
+            _ => false,
+        };
+        if !supported {
+            msg!("Unsupported mint extension: {:?}", extension);
+            return err!(VaultError::UnsupportedMintExtension);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the Token-2022 transfer fee withheld from a transfer of `amount` of `mint`, 0 for
+/// mints without a transfer fee.
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != TOKEN_2022_PROGRAM_ID {
+        return Ok(0);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<Mint>::unpack(&data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => Ok(config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(VaultError::Overflow)?),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Whether `mint` is wrapped SOL under either token program.
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
@@ -1,27 +1,79 @@
 use anchor_lang::prelude::*;
 
+pub mod auth;
+pub mod caps;
+pub mod drift;
 pub mod errors;
+pub mod fees;
+pub mod kamino;
+pub mod mint;
+pub mod pause;
+pub mod resize;
+pub mod shares;
+pub use auth::*;
+pub use caps::*;
+pub use drift::*;
 pub use errors::*;
+pub use fees::*;
+pub use kamino::*;
+pub use mint::*;
+pub use pause::*;
+pub use resize::*;
+pub use shares::*;
+
+/// Returns the index of the `kind` position for `vault_index` held in `protocol_vault` by
+/// `sub_account_id`.
+pub fn find_user_position(
+    user: &crate::state::User,
+    vault_index: u16,
+    protocol: Pubkey,
+    protocol_vault: Pubkey,
+    sub_account_id: u16,
+    kind: crate::state::PositionKind,
+) -> Result<usize> {
+    user.positions
+        .iter()
+        .position(|pos| {
+            pos.user_token_vault != Pubkey::default()
+                && pos.vault_index == vault_index
+                && pos.protocol == protocol
+                && pos.protocol_vault == protocol_vault
+                && pos.sub_account_id == sub_account_id
+                && pos.kind == kind
+        })
+        .ok_or_else(|| error!(VaultError::PositionNotFound))
+}
 
 ///
 /// Shared helpers or "controllers" for business logic.
 ///
+/// Positions are keyed by vault index, protocol vault, sub-account and kind, so the same Drift
+/// market held in two sub-accounts takes two slots. Protocols without sub-accounts pass 0 for
+/// `sub_account_id` and `market_index`.
+///
+/// Returns the position's deposited amount after the update.
 pub fn update_user_position(
     user: &mut Account<crate::state::User>,
-    user_token_vault: &mut Account<crate::state::UserTokenVault>,
     user_token_vault_key: Pubkey,
     user_token_vault_account: Pubkey,
     protocol: Pubkey,
     protocol_vault: Pubkey,
     vault_index: u16,
+    sub_account_id: u16,
+    market_index: u16,
+    kind: crate::state::PositionKind,
     delta: u64,
     withdraw: bool,
-) -> Result<()> {
+) -> Result<u64> {
     // Try to find an existing position
     let mut found_slot = None;
 
     for (i, pos) in user.positions.iter().enumerate() {
-        if pos.vault_index == vault_index && pos.protocol_vault == protocol_vault {
+        if pos.vault_index == vault_index
+            && pos.protocol_vault == protocol_vault
+            && pos.sub_account_id == sub_account_id
+            && pos.kind == kind
+        {
             found_slot = Some(i);
             break;
         }
@@ -48,8 +100,13 @@ pub fn update_user_position(
         pos.user_token_vault_account = user_token_vault_account;
         pos.protocol = protocol;
         pos.protocol_vault = protocol_vault;
+        pos.sub_account_id = sub_account_id;
+        pos.kind = kind;
     }
     require_eq!(pos.vault_index, vault_index, VaultError::InvalidVaultIndex);
+    // The protocol vault pins the market, this only fills in positions opened before the
+    // market index was recorded.
+    pos.market_index = market_index;
 
     // Update the balance
     let new_balance = if withdraw {
@@ -65,21 +122,42 @@ pub fn update_user_position(
     require_gte!(new_balance, 0, VaultError::Underflow);
     pos.deposited_amount = new_balance;
 
-    // Update the user_token_vault balance
-    let new_vault_balance = if withdraw {
-        user_token_vault
-            .deposited_amount
-            .checked_sub(delta as u128)
-            .ok_or(VaultError::Underflow)?
+    // Keep the synced value in step. Callers withdraw at most the principal here, interest above
+    // it goes through `book_withdrawn_interest`.
+    pos.value = if withdraw {
+        pos.value.saturating_sub(delta)
     } else {
-        user_token_vault
-            .deposited_amount
-            .checked_add(delta as u128)
-            .ok_or(VaultError::Overflow)?
+        pos.value.checked_add(delta).ok_or(VaultError::Overflow)?
     };
 
-    require_gte!(new_vault_balance, 0, VaultError::Underflow);
-    user_token_vault.deposited_amount = new_vault_balance;
+    Ok(new_balance)
+}
+
+/// Books the part of a protocol withdrawal from the position at `idx` that went beyond the
+/// principal it released. Interest already synced into the position's `value` is taken off it,
+/// anything above that was earned since the last sync and is booked into the user's share price.
+pub fn book_withdrawn_interest(
+    user: &mut crate::state::User,
+    idx: usize,
+    vault: &mut crate::state::SupportedTokenVault,
+    user_token_vault: &mut crate::state::UserTokenVault,
+    interest: u64,
+) -> Result<()> {
+    let pos = &mut user.positions[idx];
+    let synced = interest.min(pos.value);
+    pos.value -= synced;
+    book_position_value(vault, user_token_vault, 0, interest - synced)
+}
 
+/// Fails unless the position at `idx` is known to hold `amount`: its principal, or its value at
+/// the last sync when that is higher. Anything above that could only be borrowed from the
+/// protocol.
+pub fn ensure_within_position(user: &crate::state::User, idx: usize, amount: u64) -> Result<()> {
+    let pos = &user.positions[idx];
+    require_gte!(
+        pos.value.max(pos.deposited_amount),
+        amount,
+        VaultError::ExceedsPositionValue
+    );
     Ok(())
 }
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+use crate::controller::VaultError;
+use crate::state::{ProgramState, SupportedTokenVault};
+
+/// Fails if any of `flags` is paused globally or, when given, on `vault`.
+///
+/// `withdraw_spl` never calls this: while anything is paused users can still take their idle
+/// funds back to their wallet.
+pub fn ensure_not_paused(
+    state: &ProgramState,
+    vault: Option<&SupportedTokenVault>,
+    flags: u8,
+) -> Result<()> {
+    require!(state.paused & flags == 0, VaultError::Paused);
+    if let Some(vault) = vault {
+        require!(vault.paused & flags == 0, VaultError::VaultPaused);
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::controller::VaultError;
+
+/// Grows an account written with an older, smaller layout of the type with `discriminator` to
+/// `size` bytes. Fields added since read as zero, the payer tops up the rent.
+pub fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    discriminator: &[u8],
+    size: usize,
+) -> Result<()> {
+    {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= discriminator.len() && data[..discriminator.len()] == *discriminator,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require_gt!(size, data.len(), VaultError::AccountAlreadyResized);
+    }
+
+    let required = Rent::get()?
+        .minimum_balance(size)
+        .saturating_sub(account.lamports());
+    if required > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            required,
+        )?;
+    }
+
+    account.realloc(size, true)?;
+    Ok(())
+}
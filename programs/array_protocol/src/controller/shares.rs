@@ -0,0 +1,336 @@
+use anchor_lang::prelude::*;
+
+use crate::controller::VaultError;
+use crate::state::{SupportedTokenVault, UserTokenVault};
+
+/// `value * numerator / denominator` in 128 bits, rounded down unless `round_up`.
+pub(crate) fn mul_div(
+    value: u128,
+    numerator: u128,
+    denominator: u128,
+    round_up: bool,
+) -> Result<u128> {
+    let product = value.checked_mul(numerator).ok_or(VaultError::Overflow)?;
+    let quotient = product / denominator;
+    if round_up && product % denominator != 0 {
+        return Ok(quotient.checked_add(1).ok_or(VaultError::Overflow)?);
+    }
+    Ok(quotient)
+}
+
+fn to_shares(user_token_vault: &UserTokenVault, assets: u64, round_up: bool) -> Result<u128> {
+    if user_token_vault.shares == 0 || user_token_vault.balance == 0 {
+        return Ok(assets as u128);
+    }
+    mul_div(
+        assets as u128,
+        user_token_vault.shares,
+        user_token_vault.balance as u128,
+        round_up,
+    )
+}
+
+/// Converts between a user's assets and shares.
+///
+/// Each user's tokens sit in their own vault token account and protocol positions, so shares are
+/// priced per `UserTokenVault` against its `balance`: yield booked on one user's positions only
+/// moves that user's share price. A user without shares mints 1:1.
+///
+/// Shares minted for depositing `assets`, rounded down in favour of the vault.
+pub fn assets_to_shares(user_token_vault: &UserTokenVault, assets: u64) -> Result<u128> {
+    to_shares(user_token_vault, assets, false)
+}
+
+/// Shares burned for withdrawing `assets`, rounded up in favour of the vault.
+pub fn assets_to_shares_up(user_token_vault: &UserTokenVault, assets: u64) -> Result<u128> {
+    to_shares(user_token_vault, assets, true)
+}
+
+/// Current asset value of `shares` of `user_token_vault`, rounded down.
+pub fn shares_to_assets(user_token_vault: &UserTokenVault, shares: u128) -> Result<u64> {
+    if user_token_vault.shares == 0 {
+        return Ok(0);
+    }
+    let assets = mul_div(
+        shares,
+        user_token_vault.balance as u128,
+        user_token_vault.shares,
+        false,
+    )?;
+    Ok(u64::try_from(assets).map_err(|_| VaultError::Overflow)?)
+}
+
+/// Accounts created before share accounting hold principal but no shares. Their deposits were
+/// made 1:1, so they are converted at that rate the first time they are touched.
+pub fn migrate_legacy_shares(
+    vault: &mut SupportedTokenVault,
+    user_token_vault: &mut UserTokenVault,
+) {
+    if vault.total_shares == 0 && vault.balance > 0 {
+        vault.total_shares = vault.balance;
+    }
+    if user_token_vault.shares == 0 && user_token_vault.deposited_amount > 0 {
+        user_token_vault.shares = user_token_vault.deposited_amount;
+        user_token_vault.balance = user_token_vault.deposited_amount as u64;
+    }
+}
+
+/// Books a change in the value of the user's protocol positions, found by a position sync or a
+/// protocol withdrawal, into the user's `balance`. Yield raises the user's share price, a loss
+/// lowers it.
+pub fn book_position_value(
+    vault: &mut SupportedTokenVault,
+    user_token_vault: &mut UserTokenVault,
+    old_value: u64,
+    new_value: u64,
+) -> Result<()> {
+    migrate_legacy_shares(vault, user_token_vault);
+
+    if new_value >= old_value {
+        let gain = new_value - old_value;
+        user_token_vault.balance = user_token_vault
+            .balance
+            .checked_add(gain)
+            .ok_or(VaultError::Overflow)?;
+        vault.balance = vault
+            .balance
+            .checked_add(gain as u128)
+            .ok_or(VaultError::Overflow)?;
+    } else {
+        let loss = (old_value - new_value).min(user_token_vault.balance);
+        user_token_vault.balance -= loss;
+        vault.balance = vault.balance.saturating_sub(loss as u128);
+    }
+
+    Ok(())
+}
+
+/// Mints shares for a deposit of `amount` and records the principal.
+/// Returns the number of shares minted.
+pub fn deposit_shares(
+    vault: &mut SupportedTokenVault,
+    user_token_vault: &mut UserTokenVault,
+    amount: u64,
+) -> Result<u128> {
+    migrate_legacy_shares(vault, user_token_vault);
+
+    let shares = assets_to_shares(user_token_vault, amount)?;
+    require!(shares > 0, VaultError::ZeroShares);
+
+    vault.balance = vault
+        .balance
+        .checked_add(amount as u128)
+        .ok_or(VaultError::Overflow)?;
+    vault.total_shares = vault
+        .total_shares
+        .checked_add(shares)
+        .ok_or(VaultError::Overflow)?;
+
+    user_token_vault.shares = user_token_vault
+        .shares
+        .checked_add(shares)
+        .ok_or(VaultError::Overflow)?;
+    user_token_vault.balance = user_token_vault
+        .balance
+        .checked_add(amount)
+        .ok_or(VaultError::Overflow)?;
+    user_token_vault.deposited_amount = user_token_vault
+        .deposited_amount
+        .checked_add(amount as u128)
+        .ok_or(VaultError::Overflow)?;
+
+    Ok(shares)
+}
+
+/// Burns the shares backing a withdrawal of `amount` and releases the matching share of the
+/// principal. Returns the number of shares burned and the principal released.
+pub fn withdraw_shares(
+    vault: &mut SupportedTokenVault,
+    user_token_vault: &mut UserTokenVault,
+    amount: u64,
+) -> Result<(u128, u128)> {
+    require!(amount > 0, VaultError::ZeroAmount);
+    migrate_legacy_shares(vault, user_token_vault);
+
+    let shares = assets_to_shares_up(user_token_vault, amount)?;
+    require_gte!(
+        user_token_vault.shares,
+        shares,
+        VaultError::InsufficientShares
+    );
+
+    // Principal leaves in proportion to the shares burned, the rest is realized yield.
+    let principal = mul_div(
+        user_token_vault.deposited_amount,
+        shares,
+        user_token_vault.shares,
+        false,
+    )?;
+
+    vault.balance = vault
+        .balance
+        .checked_sub(amount as u128)
+        .ok_or(VaultError::Underflow)?;
+    vault.total_shares = vault
+        .total_shares
+        .checked_sub(shares)
+        .ok_or(VaultError::Underflow)?;
+
+    user_token_vault.shares = user_token_vault
+        .shares
+        .checked_sub(shares)
+        .ok_or(VaultError::Underflow)?;
+    user_token_vault.balance = user_token_vault
+        .balance
+        .checked_sub(amount)
+        .ok_or(VaultError::Underflow)?;
+    user_token_vault.deposited_amount = user_token_vault
+        .deposited_amount
+        .checked_sub(principal)
+        .ok_or(VaultError::Underflow)?;
+
+    Ok((shares, principal))
+}
+
+/// Moves the shares backing a withdrawal of `amount`, the matching share of the principal and the
+/// amount itself out of the user's vault so they can't be withdrawn or requested twice. Vault
+/// totals are left alone until `unlock_shares` returns them.
+/// Returns the number of shares locked and the principal locked.
+pub fn lock_shares(
+    vault: &mut SupportedTokenVault,
+    user_token_vault: &mut UserTokenVault,
+    amount: u64,
+) -> Result<(u128, u128)> {
+    require!(amount > 0, VaultError::ZeroAmount);
+    migrate_legacy_shares(vault, user_token_vault);
+
+    let shares = assets_to_shares_up(user_token_vault, amount)?;
+    require_gte!(
+        user_token_vault.shares,
+        shares,
+        VaultError::InsufficientShares
+    );
+
+    let principal = mul_div(
+        user_token_vault.deposited_amount,
+        shares,
+        user_token_vault.shares,
+        false,
+    )?;
+
+    user_token_vault.balance = user_token_vault
+        .balance
+        .checked_sub(amount)
+        .ok_or(VaultError::InsufficientShares)?;
+    user_token_vault.shares -= shares;
+    user_token_vault.deposited_amount -= principal;
+
+    Ok((shares, principal))
+}
+
+/// Returns shares, principal and the `amount` locked by `lock_shares` to the user's vault.
+pub fn unlock_shares(
+    user_token_vault: &mut UserTokenVault,
+    shares: u128,
+    principal: u128,
+    amount: u64,
+) -> Result<()> {
+    user_token_vault.shares = user_token_vault
+        .shares
+        .checked_add(shares)
+        .ok_or(VaultError::Overflow)?;
+    user_token_vault.deposited_amount = user_token_vault
+        .deposited_amount
+        .checked_add(principal)
+        .ok_or(VaultError::Overflow)?;
+    user_token_vault.balance = user_token_vault
+        .balance
+        .checked_add(amount)
+        .ok_or(VaultError::Overflow)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deposited(vault: &mut SupportedTokenVault, amount: u64) -> UserTokenVault {
+        let mut user_token_vault = UserTokenVault::default();
+        deposit_shares(vault, &mut user_token_vault, amount).unwrap();
+        user_token_vault
+    }
+
+    #[test]
+    fn synced_yield_raises_the_share_price() {
+        let mut vault = SupportedTokenVault::default();
+        let mut user = deposited(&mut vault, 1_000);
+        assert_eq!(user.shares, 1_000);
+
+        book_position_value(&mut vault, &mut user, 1_000, 1_100).unwrap();
+        assert_eq!(shares_to_assets(&user, user.shares).unwrap(), 1_100);
+        assert_eq!(vault.balance, 1_100);
+
+        // New deposits mint at the higher price
+        assert_eq!(deposit_shares(&mut vault, &mut user, 1_100).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn losses_lower_the_share_price() {
+        let mut vault = SupportedTokenVault::default();
+        let mut user = deposited(&mut vault, 1_000);
+
+        book_position_value(&mut vault, &mut user, 1_000, 900).unwrap();
+        assert_eq!(shares_to_assets(&user, user.shares).unwrap(), 900);
+        assert_eq!(vault.balance, 900);
+    }
+
+    #[test]
+    fn yield_only_moves_its_own_users_price() {
+        let mut vault = SupportedTokenVault::default();
+        let mut alice = deposited(&mut vault, 1_000);
+        let bob = deposited(&mut vault, 1_000);
+
+        book_position_value(&mut vault, &mut alice, 0, 500).unwrap();
+        assert_eq!(shares_to_assets(&alice, alice.shares).unwrap(), 1_500);
+        assert_eq!(shares_to_assets(&bob, bob.shares).unwrap(), 1_000);
+        assert_eq!(vault.balance, 2_500);
+    }
+
+    #[test]
+    fn withdrawing_after_yield_releases_less_principal() {
+        let mut vault = SupportedTokenVault::default();
+        let mut user = deposited(&mut vault, 1_000);
+        book_position_value(&mut vault, &mut user, 0, 100).unwrap();
+
+        let (shares, principal) = withdraw_shares(&mut vault, &mut user, 550).unwrap();
+        assert_eq!(shares, 500);
+        assert_eq!(principal, 500);
+        assert_eq!(user.balance, 550);
+        assert_eq!(user.deposited_amount, 500);
+    }
+
+    #[test]
+    fn zero_amounts_are_rejected() {
+        let mut vault = SupportedTokenVault::default();
+        let mut user = UserTokenVault::default();
+
+        assert!(withdraw_shares(&mut vault, &mut user, 0).is_err());
+        assert!(lock_shares(&mut vault, &mut user, 0).is_err());
+    }
+
+    #[test]
+    fn locked_amounts_keep_the_price() {
+        let mut vault = SupportedTokenVault::default();
+        let mut user = deposited(&mut vault, 1_000);
+        book_position_value(&mut vault, &mut user, 0, 1_000).unwrap();
+
+        let (shares, principal) = lock_shares(&mut vault, &mut user, 500).unwrap();
+        assert_eq!(shares, 250);
+        assert_eq!(shares_to_assets(&user, 1).unwrap(), 2);
+
+        unlock_shares(&mut user, shares, principal, 500).unwrap();
+        assert_eq!(user.balance, 2_000);
+        assert_eq!(user.shares, 1_000);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::state::RegisteredMarket;
+use anchor_lang::prelude::*;
+
+///
+/// Events emitted through `emit_cpi!` so indexers can follow deposits, withdrawals and
+/// protocol moves without parsing logs.
+///
+
+#[event]
+pub struct ProgramInitialized {
+    pub admin: Pubkey,
+    pub signer_pda: Pubkey,
+}
+
+#[event]
+pub struct UserInitialized {
+    pub user: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// `user` is the default pubkey when the program-wide token vault is created.
+#[event]
+pub struct VaultInitialized {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct VaultClosed {
+    pub user: Pubkey,
+    pub vault_index: u16,
+}
+
+#[event]
+pub struct UserClosed {
+    pub user: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct UserResized {
+    pub user: Pubkey,
+    pub position_slots: u32,
+}
+
+/// An account written with an older layout grown to `size` bytes.
+#[event]
+pub struct AccountResized {
+    pub account: Pubkey,
+    pub size: u32,
+}
+
+/// `post_balance` is the user's balance in the vault, yield included, after the deposit.
+#[event]
+pub struct Deposited {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub amount: u64,
+    pub shares: u128,
+    pub post_balance: u64,
+}
+
+/// `amount` includes `fee`, which stays in the vault until collected. `post_balance` is the
+/// user's balance in the vault, yield included, after the withdrawal.
+#[event]
+pub struct Withdrawn {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub amount: u64,
+    pub fee: u64,
+    pub shares: u128,
+    pub post_balance: u64,
+}
+
+/// `post_balance` is the position's deposited amount after the deposit.
+#[event]
+pub struct ProtocolDeposited {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub protocol: Pubkey,
+    pub protocol_vault: Pubkey,
+    pub amount: u64,
+    pub post_balance: u64,
+}
+
+/// `post_balance` is the position's deposited amount after the withdrawal.
+#[event]
+pub struct ProtocolWithdrawn {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub protocol: Pubkey,
+    pub protocol_vault: Pubkey,
+    pub amount: u64,
+    pub post_balance: u64,
+}
+
+/// `user_balance` is the user's `UserTokenVault.balance` after booking the change in `value`.
+#[event]
+pub struct PositionUpdated {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub protocol: Pubkey,
+    pub protocol_vault: Pubkey,
+    pub value: u64,
+    pub deposited_amount: u64,
+    pub user_balance: u64,
+    pub slot: u64,
+}
+
+/// `shares` and the matching principal stay locked out of the user's vault until the request
+/// is executed or cancelled.
+#[event]
+pub struct WithdrawalRequested {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub amount: u64,
+    pub shares: u128,
+    pub executable_at: i64,
+}
+
+/// `principal` is the staked principal moved to the unstaking position, `slot` the slot stamped
+/// on it.
+#[event]
+pub struct InsuranceFundUnstakeRequested {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub market_index: u16,
+    pub amount: u64,
+    pub principal: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct WithdrawalCancelled {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub amount: u64,
+}
+
+/// A user account created in an external protocol, e.g. a Drift user or a klend obligation.
+#[event]
+pub struct ProtocolAccountInitialized {
+    pub user: Pubkey,
+    pub protocol: Pubkey,
+    pub account: Pubkey,
+}
+
+#[event]
+pub struct ProtocolAccountClosed {
+    pub user: Pubkey,
+    pub protocol: Pubkey,
+    pub account: Pubkey,
+}
+
+#[event]
+pub struct DelegateUpdated {
+    pub user: Pubkey,
+    pub delegate: Pubkey,
+    pub permissions: u8,
+}
+
+#[event]
+pub struct DriftDelegateUpdated {
+    pub user: Pubkey,
+    pub drift_user: Pubkey,
+    pub delegate: Pubkey,
+}
+
+/// `post_balance` is the fees still accrued on the user's vault after collection.
+#[event]
+pub struct FeesCollected {
+    pub user: Pubkey,
+    pub vault_index: u16,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub post_balance: u64,
+}
+
+#[event]
+pub struct AdminUpdated {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct TreasuryUpdated {
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct VaultFeesUpdated {
+    pub vault_index: u16,
+    pub performance_fee_bps: u16,
+    pub management_fee_bps: u16,
+}
+
+/// `vault_index` is `None` for the global pause flags.
+#[event]
+pub struct PauseUpdated {
+    pub vault_index: Option<u16>,
+    pub paused: u8,
+}
+
+#[event]
+pub struct WithdrawalDelayUpdated {
+    pub vault_index: u16,
+    pub withdrawal_delay: i64,
+}
+
+#[event]
+pub struct DepositCapsUpdated {
+    pub vault_index: u16,
+    pub max_total_deposits: u64,
+    pub max_user_deposits: u64,
+}
+
+#[event]
+pub struct RegistryUpdated {
+    pub vault_index: u16,
+    pub market: RegisteredMarket,
+    pub registered: bool,
+}
+
+/// An empty registry created for a vault initialized before registries existed.
+#[event]
+pub struct RegistryInitialized {
+    pub vault_index: u16,
+    pub registry: Pubkey,
+}
@@ -11,3 +11,9 @@ pub mod drift {
     #[cfg(not(feature = "devnet"))]
     declare_id!("DftNc7gwihkEEwQRpu4bV89N18xpNEuBVg7YkhTZZhVo"); // Local ID
 }
+
+pub mod kamino_farms {
+    use anchor_lang::solana_program::declare_id;
+
+    declare_id!("FarmsPZpWu9i7Kky8tPN37rs2TpmMrAZrC7S7vJa91Hr");
+}
@@ -0,0 +1,36 @@
+use crate::controller::VaultError;
+use crate::events::AdminUpdated;
+use crate::state::ProgramState;
+use anchor_lang::prelude::*;
+
+/// Accounts for `accept_admin`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = pending_admin @ VaultError::NotPendingAdmin
+    )]
+    pub state: Account<'info, ProgramState>,
+}
+
+/// Handler for `accept_admin`.
+/// Second step of an admin transfer, signed by the proposed admin.
+pub fn handle_accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let state = &mut ctx.accounts.state;
+    state.admin = state.pending_admin;
+    state.pending_admin = Pubkey::default();
+
+    msg!("Admin transferred to {}", state.admin);
+
+    emit_cpi!(AdminUpdated {
+        admin: state.admin,
+        pending_admin: Pubkey::default(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use crate::controller::VaultError;
+use crate::events::RegistryUpdated;
+use crate::state::{ProgramState, ProtocolRegistry, RegisteredMarket, MAX_REGISTERED_MARKETS};
+use anchor_lang::prelude::*;
+
+/// Accounts for `add_registry_market` and `remove_registry_market`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct UpdateRegistry<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol_registry".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub registry: Box<Account<'info, ProtocolRegistry>>,
+}
+
+/// Handler for `add_registry_market`.
+pub fn handle_add_registry_market(
+    ctx: Context<UpdateRegistry>,
+    _vault_index: u16,
+    market: RegisteredMarket,
+) -> Result<()> {
+    // Markets are compared as a whole, don't store padding the protocol checks won't have
+    let market = RegisteredMarket {
+        _padding: [0; 6],
+        ..market
+    };
+    let registry = &mut ctx.accounts.registry;
+    require!(
+        !registry.is_registered(&market),
+        VaultError::MarketAlreadyRegistered
+    );
+    require!(
+        (registry.len as usize) < MAX_REGISTERED_MARKETS,
+        VaultError::RegistryFull
+    );
+
+    let len = registry.len as usize;
+    registry.markets[len] = market;
+    registry.len += 1;
+
+    msg!(
+        "Registered market {} of {} for vault {}",
+        market.market,
+        market.protocol,
+        registry.token_vault_index
+    );
+
+    emit_cpi!(RegistryUpdated {
+        vault_index: registry.token_vault_index,
+        market,
+        registered: true,
+    });
+
+    Ok(())
+}
+
+/// Handler for `remove_registry_market`.
+/// Only new deposits are affected, positions already in the market can still be withdrawn.
+pub fn handle_remove_registry_market(
+    ctx: Context<UpdateRegistry>,
+    _vault_index: u16,
+    market: RegisteredMarket,
+) -> Result<()> {
+    let market = RegisteredMarket {
+        _padding: [0; 6],
+        ..market
+    };
+    let registry = &mut ctx.accounts.registry;
+    let idx = registry
+        .markets()
+        .iter()
+        .position(|m| *m == market)
+        .ok_or(VaultError::MarketNotRegistered)?;
+
+    // Swap the last entry into the freed slot
+    let last = registry.len as usize - 1;
+    registry.markets[idx] = registry.markets[last];
+    registry.markets[last] = RegisteredMarket::default();
+    registry.len -= 1;
+
+    msg!(
+        "Removed market {} of {} from vault {}",
+        market.market,
+        market.protocol,
+        registry.token_vault_index
+    );
+
+    emit_cpi!(RegistryUpdated {
+        vault_index: registry.token_vault_index,
+        market,
+        registered: false,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::controller::{is_authorized, UserAction, VaultError};
+use crate::events::UserResized;
+use crate::state::{User, MAX_POSITION_SLOTS};
+use anchor_lang::prelude::*;
+
+/// Accounts for `add_user_positions`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(count: u8)]
+pub struct AddUserPositions<'info> {
+    /// Pays the rent for the added slots.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        realloc = User::space(user_state.positions.len() + count as usize),
+        realloc::payer = signer,
+        realloc::zero = false,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::ManageAccount) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for `add_user_positions`.
+/// Appends `count` empty position slots to the user.
+pub fn handle_add_user_positions(ctx: Context<AddUserPositions>, count: u8) -> Result<()> {
+    let user_state = &mut ctx.accounts.user_state;
+    let slots = user_state.positions.len() + count as usize;
+    require_gte!(MAX_POSITION_SLOTS, slots, VaultError::TooManyPositions);
+
+    user_state.positions.resize(slots, Default::default());
+
+    msg!("User now has {} position slots", slots);
+
+    emit_cpi!(UserResized {
+        user: ctx.accounts.user_state.key(),
+        position_slots: slots as u32,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use crate::controller::{is_authorized, unlock_shares, UserAction, VaultError};
+use crate::events::WithdrawalCancelled;
+use crate::state::{User, UserTokenVault, WithdrawalRequest};
+use anchor_lang::prelude::*;
+
+/// Accounts for `cancel_withdraw`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct CancelWithdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Transfer) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Account<'info, UserTokenVault>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"withdrawal_request".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+}
+
+/// Handler for `cancel_withdraw`.
+/// Returns the locked shares, principal and amount to the user's vault.
+pub fn handle_cancel_withdraw(ctx: Context<CancelWithdraw>, vault_index: u16) -> Result<()> {
+    let request = &ctx.accounts.withdrawal_request;
+    unlock_shares(
+        &mut ctx.accounts.user_token_vault,
+        request.shares,
+        request.principal,
+        request.amount,
+    )?;
+
+    msg!("Cancelled withdrawal of {}", request.amount);
+
+    emit_cpi!(WithdrawalCancelled {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        amount: ctx.accounts.withdrawal_request.amount,
+    });
+
+    Ok(())
+}
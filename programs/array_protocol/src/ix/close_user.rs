@@ -0,0 +1,73 @@
+use crate::controller::VaultError;
+use crate::events::UserClosed;
+use crate::state::{ProgramState, User};
+use anchor_lang::prelude::*;
+
+/// Accounts for `close_user`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseUser<'info> {
+    /// Receives the rent of the closed account.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"user", authority.key().as_ref()],
+        bump = user_state.bump,
+        has_one = authority @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+}
+
+/// Handler for `close_user`.
+/// Closes the `User` account once every position is empty and every user vault is closed,
+/// returning the rent to the authority. The user vault PDA of each vault index, in order, is
+/// passed as remaining accounts. `close_user_token_vault` closes the idle token account with the
+/// user vault, so no tokens are left behind under the closed user.
+pub fn handle_close_user(ctx: Context<CloseUser>) -> Result<()> {
+    require!(
+        !ctx.accounts
+            .user_state
+            .positions
+            .iter()
+            .any(|pos| pos.is_open()),
+        VaultError::PositionOpen
+    );
+
+    let user_state = ctx.accounts.user_state.key();
+    let token_vault_count = ctx.accounts.state.token_vault_count;
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        token_vault_count as usize,
+        ErrorCode::AccountNotEnoughKeys
+    );
+    for (vault_index, user_token_vault) in (0..token_vault_count).zip(ctx.remaining_accounts) {
+        let (expected, _) = Pubkey::find_program_address(
+            &[
+                b"user_vault".as_ref(),
+                user_state.as_ref(),
+                vault_index.to_le_bytes().as_ref(),
+            ],
+            &crate::ID,
+        );
+        require_keys_eq!(user_token_vault.key(), expected, ErrorCode::ConstraintSeeds);
+        require!(user_token_vault.data_is_empty(), VaultError::UserVaultOpen);
+    }
+
+    msg!("Closed user {}", ctx.accounts.user_state.key());
+
+    emit_cpi!(UserClosed {
+        user: ctx.accounts.user_state.key(),
+        authority: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use crate::controller::VaultError;
+use crate::events::VaultClosed;
+use crate::get_user_seeds;
+use crate::state::{Position, User, UserTokenVault};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{CloseAccount, TokenAccount, TokenInterface};
+
+/// Accounts for `close_user_token_vault`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct CloseUserTokenVault<'info> {
+    /// Receives the rent of both closed accounts.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump = user_state.bump,
+        has_one = authority @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Box<Account<'info, User>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        constraint = user_token_vault.shares == 0 @ VaultError::VaultNotEmpty
+    )]
+    pub user_token_vault: Box<Account<'info, UserTokenVault>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        token::token_program = token_program,
+        constraint = user_token_vault_account.amount == 0 @ VaultError::VaultNotEmpty
+    )]
+    pub user_token_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: only checked to be empty, a pending request holds shares of this vault
+    #[account(
+        seeds = [b"withdrawal_request".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        constraint = withdrawal_request.data_is_empty() @ VaultError::WithdrawalRequestPending
+    )]
+    pub withdrawal_request: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Handler for `close_user_token_vault`.
+/// Closes an empty user vault and its token account, returning the rent to the authority. Empty
+/// position slots left behind for the vault are freed.
+pub fn handle_close_user_token_vault(
+    ctx: Context<CloseUserTokenVault>,
+    vault_index: u16,
+) -> Result<()> {
+    let user_state = &mut ctx.accounts.user_state;
+    require!(
+        !user_state
+            .positions
+            .iter()
+            .any(|pos| pos.vault_index == vault_index && pos.is_open()),
+        VaultError::PositionOpen
+    );
+    for pos in user_state
+        .positions
+        .iter_mut()
+        .filter(|pos| pos.user_token_vault != Pubkey::default() && pos.vault_index == vault_index)
+    {
+        *pos = Position::default();
+    }
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.user_token_vault_account.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.user_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    anchor_spl::token_interface::close_account(cpi_ctx)?;
+
+    msg!("Closed user token vault for vault index: {}", vault_index);
+
+    emit_cpi!(VaultClosed {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+    });
+
+    Ok(())
+}
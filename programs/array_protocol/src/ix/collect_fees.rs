@@ -0,0 +1,125 @@
+use crate::controller::{accrue_management_fee, ensure_not_paused, settle_fees, VaultError};
+use crate::events::FeesCollected;
+use crate::get_user_seeds;
+use crate::state::{ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_WITHDRAWALS};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+/// Accounts for `collect_fees`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct CollectFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin,
+        constraint = state.treasury != Pubkey::default() @ VaultError::TreasuryNotSet
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(owner = token_program.key())]
+    pub token_vault_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        constraint = token_vault.mint == token_vault_mint.key()
+    )]
+    pub token_vault: Account<'info, SupportedTokenVault>,
+
+    #[account(
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Account<'info, UserTokenVault>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        token::mint = token_vault_mint,
+        token::authority = user_state,
+        token::token_program = token_program
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_vault_mint,
+        token::authority = state.treasury,
+        token::token_program = token_program
+    )]
+    pub treasury_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Handler for `collect_fees`.
+/// Charges the user's pending management fee and sends their accrued fees to the treasury. Fees
+/// are paid from idle funds only, anything still deployed in a protocol stays accrued.
+pub fn handle_collect_fees(ctx: Context<CollectFees>, _vault_index: u16) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_WITHDRAWALS,
+    )?;
+
+    let token_vault = &mut ctx.accounts.token_vault;
+    let user_token_vault = &mut ctx.accounts.user_token_vault;
+    accrue_management_fee(token_vault, user_token_vault, Clock::get()?.unix_timestamp)?;
+
+    let amount = settle_fees(
+        token_vault,
+        user_token_vault,
+        ctx.accounts.user_vault_token_account.amount,
+    )?;
+    if amount == 0 {
+        msg!("No fees to collect");
+        return Ok(());
+    }
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            mint: ctx.accounts.token_vault_mint.to_account_info(),
+            from: ctx.accounts.user_vault_token_account.to_account_info(),
+            to: ctx.accounts.treasury_token_account.to_account_info(),
+            authority: ctx.accounts.user_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    anchor_spl::token_interface::transfer_checked(
+        cpi_ctx,
+        amount,
+        ctx.accounts.token_vault_mint.decimals,
+    )?;
+
+    msg!("Collected {} in fees", amount);
+
+    emit_cpi!(FeesCollected {
+        user: ctx.accounts.user_state.key(),
+        vault_index: ctx.accounts.token_vault.token_vault_index,
+        treasury: ctx.accounts.state.treasury,
+        amount,
+        post_balance: ctx.accounts.user_token_vault.accrued_fees,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use crate::controller::{
+    accrue_management_fee, deposit_shares, ensure_not_paused, ensure_within_deposit_caps,
+    is_authorized, is_native_mint, UserAction, VaultError,
+};
+use crate::events::Deposited;
+use crate::state::{ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_DEPOSITS};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_interface::{Mint, SyncNative, TokenAccount, TokenInterface};
+
+/// Accounts for `deposit_sol`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct DepositSol<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        owner = token_program.key(),
+        constraint = is_native_mint(&token_vault_mint.key()) @ VaultError::NotNativeMint
+    )]
+    pub token_vault_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Transfer) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        token::mint = token_vault_mint,
+        token::authority = user_state,
+        token::token_program = token_program
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Account<'info, UserTokenVault>,
+
+    /// TokenVault metadata
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub protocol_token_vault: Account<'info, SupportedTokenVault>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Handler for `deposit_sol`.
+/// Wraps `amount` lamports from the signer straight into the user's wSOL vault account and
+/// credits them like `deposit_spl`.
+pub fn handle_deposit_sol(ctx: Context<DepositSol>, vault_index: u16, amount: u64) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.protocol_token_vault),
+        PAUSE_DEPOSITS,
+    )?;
+
+    // 1) Move the lamports into the wSOL account and sync its token balance
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.signer.to_account_info(),
+                to: ctx.accounts.user_vault_token_account.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    anchor_spl::token_interface::sync_native(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        SyncNative {
+            account: ctx.accounts.user_vault_token_account.to_account_info(),
+        },
+    ))?;
+
+    // 2) Charge the management fee owed so far, then mint vault shares at the current rate and
+    // record the principal
+    let vault = &mut ctx.accounts.protocol_token_vault;
+    let user_token_vault = &mut ctx.accounts.user_token_vault;
+    ensure_within_deposit_caps(vault, user_token_vault, amount)?;
+    accrue_management_fee(vault, user_token_vault, Clock::get()?.unix_timestamp)?;
+    let shares = deposit_shares(vault, user_token_vault, amount)?;
+    msg!("Deposited {} lamports for {} shares", amount, shares);
+
+    emit_cpi!(Deposited {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        amount,
+        shares,
+        post_balance: ctx.accounts.user_token_vault.balance,
+    });
+
+    Ok(())
+}
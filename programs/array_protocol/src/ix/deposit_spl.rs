@@ -1,7 +1,11 @@
-use crate::state::{SupportedTokenVault, User, UserTokenVault};
+use crate::controller::{
+    accrue_management_fee, deposit_shares, ensure_not_paused, ensure_within_deposit_caps,
+    is_authorized, UserAction, VaultError,
+};
+use crate::events::Deposited;
+use crate::state::{ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_DEPOSITS};
 use anchor_lang::prelude::*;
-use anchor_spl::token::TransferChecked;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
 /// Accounts for `deposit_spl`.
 /// To do a deposit we will need the following:
@@ -11,6 +15,7 @@ use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 /// 4. The user token account --> this is the user's token account, we will transfer the tokens to this pda
 /// 4. The user token vault --> this is the object for the user's token account, this helps us keep track of their position / positions in protocols
 /// 5. The protocol vault account --> this is to track the protocol level of the balance for the token vault
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(vault_index: u16)]
 pub struct DepositSpl<'info> {
@@ -20,9 +25,20 @@ pub struct DepositSpl<'info> {
     #[account(owner = token_program.key())]
     pub token_vault_mint: InterfaceAccount<'info, Mint>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Transfer) @ VaultError::UnauthorizedUser
+    )]
     pub user_state: Account<'info, User>,
 
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
     #[account(
         mut,
         token::authority = signer
@@ -63,7 +79,13 @@ pub struct DepositSpl<'info> {
 }
 
 /// Handler function for `deposit_spl`.
-pub fn handle_deposit_spl(ctx: Context<DepositSpl>, _vault_index: u16, amount: u64) -> Result<()> {
+pub fn handle_deposit_spl(ctx: Context<DepositSpl>, vault_index: u16, amount: u64) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.protocol_token_vault),
+        PAUSE_DEPOSITS,
+    )?;
+
     let token_program = &ctx.accounts.token_program;
     let decimals = ctx.accounts.token_vault_mint.decimals;
 
@@ -76,21 +98,34 @@ pub fn handle_deposit_spl(ctx: Context<DepositSpl>, _vault_index: u16, amount: u
 
     let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
 
-    anchor_spl::token::transfer_checked(cpi_ctx, amount, decimals)?;
-
-    // 2) Update vault global balance
-    let vault_state = &mut ctx.accounts.protocol_token_vault;
-    vault_state.balance = vault_state
-        .balance
-        .checked_add(amount as u128)
-        .ok_or(crate::controller::errors::VaultError::Overflow)?;
-
-    // 3) Update user position
+    let balance_before = ctx.accounts.user_vault_token_account.amount;
+    anchor_spl::token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
+
+    // Token-2022 transfer fees are withheld from the destination, credit what actually arrived
+    ctx.accounts.user_vault_token_account.reload()?;
+    let received = ctx
+        .accounts
+        .user_vault_token_account
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(VaultError::Underflow)?;
+
+    // 2) Charge the management fee owed so far, then mint vault shares at the current rate and
+    // record the principal
+    let vault = &mut ctx.accounts.protocol_token_vault;
     let user_token_vault = &mut ctx.accounts.user_token_vault;
-    user_token_vault.deposited_amount = user_token_vault
-        .deposited_amount
-        .checked_add(amount as u128)
-        .ok_or(crate::controller::errors::VaultError::Overflow)?;
+    ensure_within_deposit_caps(vault, user_token_vault, received)?;
+    accrue_management_fee(vault, user_token_vault, Clock::get()?.unix_timestamp)?;
+    let shares = deposit_shares(vault, user_token_vault, received)?;
+    msg!("Deposited {} for {} shares", received, shares);
+
+    emit_cpi!(Deposited {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        amount: received,
+        shares,
+        post_balance: ctx.accounts.user_token_vault.balance,
+    });
 
     Ok(())
 }
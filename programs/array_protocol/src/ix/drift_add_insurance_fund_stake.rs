@@ -0,0 +1,200 @@
+use crate::controller::{
+    drift_insurance_fund_stake_address, drift_insurance_fund_vault_address, drift_signer_address,
+    drift_spot_market_address, drift_spot_market_vault_address, drift_state_address,
+    drift_user_stats_address, ensure_not_paused, is_authorized, transfer_fee, update_user_position,
+    UserAction, VaultError,
+};
+use crate::events::ProtocolDeposited;
+use crate::state::{
+    PositionKind, ProgramState, ProtocolRegistry, RegisteredMarket, SupportedTokenVault, User,
+    UserTokenVault, PAUSE_DEPOSITS, PAUSE_PROTOCOLS,
+};
+use crate::{drift, get_user_seeds};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use drift::program::Drift;
+
+/// Accounts for `add_drift_insurance_fund_stake`.
+/// Drift's remaining accounts (oracles, spot markets) are passed as remaining accounts.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16, market_index: u16)]
+pub struct AddDriftInsuranceFundStake<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(owner = token_program.key())]
+    pub token_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Drift) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(
+        seeds = [b"token_vault", &vault_index.to_le_bytes()],
+        bump
+    )]
+    pub token_vault: Box<Account<'info, SupportedTokenVault>>,
+
+    #[account(
+        seeds = [b"protocol_registry".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub registry: Box<Account<'info, ProtocolRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        token::mint = token_vault_mint,
+        token::authority = user_state,
+        token::token_program = token_program
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Box<Account<'info, UserTokenVault>>,
+
+    /// CHECK: Drift's state account, checked against its derived address
+    #[account(address = drift_state_address() @ VaultError::InvalidDriftAccount)]
+    pub drift_state: AccountInfo<'info>,
+
+    /// CHECK: Drift spot market of `market_index`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_spot_market_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
+    pub spot_market: AccountInfo<'info>,
+
+    /// CHECK: Drift insurance fund stake of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_insurance_fund_stake_address(&user_state.key(), market_index) @ VaultError::InvalidDriftAccount
+    )]
+    pub insurance_fund_stake: AccountInfo<'info>,
+
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
+    pub drift_user_stats: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        address = drift_spot_market_vault_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
+    pub spot_market_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = drift_insurance_fund_vault_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
+    pub insurance_fund_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Drift's signer PDA, checked against its derived address
+    #[account(address = drift_signer_address() @ VaultError::InvalidDriftAccount)]
+    pub drift_signer: AccountInfo<'info>,
+
+    /// Drift Program
+    pub drift_program: Program<'info, Drift>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Handler for `add_drift_insurance_fund_stake`.
+/// Stakes idle vault funds in the insurance fund of `market_index`, which must be registered for
+/// the vault. The stake is tracked as an `InsuranceFundStake` position.
+pub fn handle_add_drift_insurance_fund_stake<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, AddDriftInsuranceFundStake<'info>>,
+    vault_index: u16,
+    market_index: u16,
+    amount: u64,
+) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_DEPOSITS | PAUSE_PROTOCOLS,
+    )?;
+
+    require!(
+        ctx.accounts
+            .registry
+            .is_registered(&RegisteredMarket::drift_insurance_fund(
+                ctx.accounts.drift_program.key(),
+                ctx.accounts.insurance_fund_vault.key(),
+                market_index,
+            )),
+        VaultError::MarketNotRegistered
+    );
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = drift::cpi::accounts::AddInsuranceFundStake {
+        state: ctx.accounts.drift_state.to_account_info(),
+        spot_market: ctx.accounts.spot_market.to_account_info(),
+        insurance_fund_stake: ctx.accounts.insurance_fund_stake.to_account_info(),
+        user_stats: ctx.accounts.drift_user_stats.to_account_info(),
+        authority: ctx.accounts.user_state.to_account_info(),
+        spot_market_vault: ctx.accounts.spot_market_vault.to_account_info(),
+        insurance_fund_vault: ctx.accounts.insurance_fund_vault.to_account_info(),
+        drift_signer: ctx.accounts.drift_signer.to_account_info(),
+        user_token_account: ctx.accounts.user_vault_token_account.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.drift_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    drift::cpi::add_insurance_fund_stake(cpi_ctx, market_index, amount)?;
+
+    // Token-2022 transfer fees are withheld from what reaches the protocol
+    let received = amount
+        .checked_sub(transfer_fee(
+            &ctx.accounts.token_vault_mint.to_account_info(),
+            amount,
+        )?)
+        .ok_or(VaultError::Underflow)?;
+
+    let post_balance = update_user_position(
+        &mut ctx.accounts.user_state,
+        ctx.accounts.user_token_vault.key(),
+        ctx.accounts.user_vault_token_account.key(),
+        ctx.accounts.drift_program.key(),
+        ctx.accounts.insurance_fund_vault.key(),
+        vault_index,
+        0,
+        market_index,
+        PositionKind::InsuranceFundStake,
+        received,
+        false,
+    )?;
+
+    emit_cpi!(ProtocolDeposited {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol: ctx.accounts.drift_program.key(),
+        protocol_vault: ctx.accounts.insurance_fund_vault.key(),
+        amount: received,
+        post_balance,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use crate::controller::{
+    drift_state_address, drift_user_address, drift_user_stats_address, ensure_not_paused,
+    VaultError,
+};
+use crate::events::ProtocolAccountClosed;
+use crate::state::{Position, PositionKind, ProgramState, User, PAUSE_PROTOCOLS};
+use crate::{drift, get_user_seeds};
+use anchor_lang::prelude::*;
+use drift::program::Drift;
+
+/// Accounts for `delete_drift_user`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(sub_account_id: u16)]
+pub struct DeleteDriftUser<'info> {
+    /// Receives the rent of the deleted Drift user.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump = user_state.bump,
+        has_one = authority @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    /// CHECK: Drift's state account, checked against its derived address
+    #[account(mut, address = drift_state_address() @ VaultError::InvalidDriftAccount)]
+    pub drift_state: AccountInfo<'info>,
+
+    /// CHECK: Drift user of `user_state`'s sub-account, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_address(&user_state.key(), sub_account_id) @ VaultError::InvalidDriftAccount
+    )]
+    pub drift_user: AccountInfo<'info>,
+
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
+    pub drift_user_stats: AccountInfo<'info>,
+
+    /// Drift Program
+    pub drift_program: Program<'info, Drift>,
+}
+
+/// Handler for `delete_drift_user`.
+/// Deletes an empty Drift sub-account. Drift returns the rent to the `User` PDA, which passes it
+/// on to the authority. Empty position slots left behind for the sub-account are freed.
+pub fn handle_delete_drift_user(ctx: Context<DeleteDriftUser>, sub_account_id: u16) -> Result<()> {
+    ensure_not_paused(&ctx.accounts.state, None, PAUSE_PROTOCOLS)?;
+
+    let drift_program_key = ctx.accounts.drift_program.key();
+    let user_state = &mut ctx.accounts.user_state;
+    let in_sub_account = |pos: &Position| {
+        pos.kind == PositionKind::Lending
+            && pos.protocol == drift_program_key
+            && pos.sub_account_id == sub_account_id
+    };
+    require!(
+        !user_state
+            .positions
+            .iter()
+            .any(|pos| in_sub_account(pos) && pos.is_open()),
+        VaultError::PositionOpen
+    );
+    for pos in user_state
+        .positions
+        .iter_mut()
+        .filter(|pos| pos.user_token_vault != Pubkey::default() && in_sub_account(pos))
+    {
+        *pos = Position::default();
+    }
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let user_state_info = ctx.accounts.user_state.to_account_info();
+    let lamports_before = user_state_info.lamports();
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.drift_program.to_account_info(),
+        drift::cpi::accounts::DeleteUser {
+            user: ctx.accounts.drift_user.to_account_info(),
+            user_stats: ctx.accounts.drift_user_stats.to_account_info(),
+            state: ctx.accounts.drift_state.to_account_info(),
+            authority: user_state_info.clone(),
+        },
+        signer_seeds,
+    );
+    drift::cpi::delete_user(cpi_ctx)?;
+
+    // Forward the reclaimed rent from the User PDA to the authority
+    let rent = user_state_info
+        .lamports()
+        .checked_sub(lamports_before)
+        .ok_or(VaultError::Underflow)?;
+    **user_state_info.try_borrow_mut_lamports()? -= rent;
+    **ctx.accounts.authority.try_borrow_mut_lamports()? += rent;
+
+    msg!(
+        "Deleted Drift sub-account {}, returned {} lamports",
+        sub_account_id,
+        rent
+    );
+
+    emit_cpi!(ProtocolAccountClosed {
+        user: ctx.accounts.user_state.key(),
+        protocol: drift_program_key,
+        account: ctx.accounts.drift_user.key(),
+    });
+
+    Ok(())
+}
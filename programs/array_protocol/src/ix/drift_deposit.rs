@@ -1,12 +1,21 @@
-use crate::controller::update_user_position;
-use crate::state::{SupportedTokenVault, User, UserTokenVault};
+use crate::controller::{
+    drift_delegate, drift_spot_market_vault_address, drift_state_address, drift_user_address,
+    drift_user_stats_address, ensure_not_paused, is_authorized, transfer_fee, update_user_position,
+    UserAction, VaultError,
+};
+use crate::events::{DriftDelegateUpdated, ProtocolAccountInitialized, ProtocolDeposited};
+use crate::state::{
+    PositionKind, ProgramState, ProtocolRegistry, RegisteredMarket, SupportedTokenVault, User,
+    UserTokenVault, PAUSE_DEPOSITS, PAUSE_PROTOCOLS,
+};
 use crate::{drift, get_user_seeds};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use drift::program::Drift;
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(vault_index: u16)]
+#[instruction(vault_index: u16, sub_account_id: u16, market_index: u16)]
 pub struct DriftDeposit<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -14,9 +23,20 @@ pub struct DriftDeposit<'info> {
     #[account(owner = token_program.key())]
     pub token_vault_mint: InterfaceAccount<'info, Mint>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Drift) @ VaultError::UnauthorizedUser
+    )]
     pub user_state: Account<'info, User>,
 
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
     #[account(
         mut,
         seeds = [b"token_vault", &vault_index.to_le_bytes()],
@@ -24,6 +44,12 @@ pub struct DriftDeposit<'info> {
     )]
     pub token_vault: Account<'info, SupportedTokenVault>,
 
+    #[account(
+        seeds = [b"protocol_registry".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub registry: Box<Account<'info, ProtocolRegistry>>,
+
     #[account(
         mut,
         seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
@@ -41,20 +67,28 @@ pub struct DriftDeposit<'info> {
     )]
     pub user_token_vault: Account<'info, UserTokenVault>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: Drift's state account, checked against its derived address
+    #[account(mut, address = drift_state_address() @ VaultError::InvalidDriftAccount)]
     pub drift_state: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: Drift user of `user_state`'s sub-account, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_address(&user_state.key(), sub_account_id) @ VaultError::InvalidDriftAccount
+    )]
     pub drift_user: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
     pub drift_user_stats: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    #[account(
+        mut,
+        address = drift_spot_market_vault_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
     pub spot_market_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: program signer
@@ -67,12 +101,32 @@ pub struct DriftDeposit<'info> {
     pub token_program: Interface<'info, TokenInterface>,
 }
 
+/// Deposits idle vault funds into a Drift spot market from `sub_account_id`. The Drift user stats
+/// and user are created first if they don't exist yet, so a first deposit is a single call.
 pub fn handle_drift_deposit<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, DriftDeposit<'info>>,
     vault_index: u16,
+    sub_account_id: u16,
     market_index: u16,
     amount: u64,
 ) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_DEPOSITS | PAUSE_PROTOCOLS,
+    )?;
+
+    require!(
+        ctx.accounts
+            .registry
+            .is_registered(&RegisteredMarket::drift(
+                ctx.accounts.drift_program.key(),
+                ctx.accounts.spot_market_vault.key(),
+                market_index,
+            )),
+        VaultError::MarketNotRegistered
+    );
+
     let drift_program = ctx.accounts.drift_program.to_account_info();
 
     let seeds = get_user_seeds(
@@ -81,13 +135,78 @@ pub fn handle_drift_deposit<'c: 'info, 'info>(
     );
     let signer_seeds = &[&seeds[..]];
 
+    // A first deposit creates the Drift accounts, the signer pays their rent
+    if ctx.accounts.drift_user_stats.data_is_empty() {
+        let init_stats_ctx = CpiContext::new_with_signer(
+            drift_program.clone(),
+            drift::cpi::accounts::InitializeUserStats {
+                user_stats: ctx.accounts.drift_user_stats.to_account_info(),
+                state: ctx.accounts.drift_state.to_account_info(),
+                authority: ctx.accounts.user_state.to_account_info(),
+                payer: ctx.accounts.signer.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            signer_seeds,
+        );
+        drift::cpi::initialize_user_stats(init_stats_ctx)?;
+
+        emit_cpi!(ProtocolAccountInitialized {
+            user: ctx.accounts.user_state.key(),
+            protocol: ctx.accounts.drift_program.key(),
+            account: ctx.accounts.drift_user_stats.key(),
+        });
+    }
+
+    if ctx.accounts.drift_user.data_is_empty() {
+        let init_ctx = CpiContext::new_with_signer(
+            drift_program.clone(),
+            drift::cpi::accounts::InitializeUser {
+                user: ctx.accounts.drift_user.to_account_info(),
+                user_stats: ctx.accounts.drift_user_stats.to_account_info(),
+                state: ctx.accounts.drift_state.to_account_info(),
+                authority: ctx.accounts.user_state.to_account_info(),
+                payer: ctx.accounts.signer.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            signer_seeds,
+        );
+        drift::cpi::initialize_user(init_ctx, sub_account_id, [0u8; 32])?;
+
+        emit_cpi!(ProtocolAccountInitialized {
+            user: ctx.accounts.user_state.key(),
+            protocol: ctx.accounts.drift_program.key(),
+            account: ctx.accounts.drift_user.key(),
+        });
+
+        let delegate = drift_delegate(&ctx.accounts.user_state);
+        if delegate != Pubkey::default() {
+            let delegate_ctx = CpiContext::new_with_signer(
+                drift_program.clone(),
+                drift::cpi::accounts::UpdateUserDelegate {
+                    user: ctx.accounts.drift_user.to_account_info(),
+                    authority: ctx.accounts.user_state.to_account_info(),
+                },
+                signer_seeds,
+            );
+            drift::cpi::update_user_delegate(delegate_ctx, sub_account_id, delegate)?;
+
+            emit_cpi!(DriftDelegateUpdated {
+                user: ctx.accounts.user_state.key(),
+                drift_user: ctx.accounts.drift_user.key(),
+                delegate,
+            });
+        }
+    }
+
     let cpi_accounts = drift::cpi::accounts::Deposit {
         state: ctx.accounts.drift_state.to_account_info(),
-        user: ctx.accounts.drift_user.to_account_info(), // TODO: this is a pda, we need to create this account if it doens't exist here.
-        user_stats: ctx.accounts.drift_user_stats.to_account_info(), // TODO: this is a pda
+        user: ctx.accounts.drift_user.to_account_info(),
+        user_stats: ctx.accounts.drift_user_stats.to_account_info(),
         authority: ctx.accounts.user_state.to_account_info(),
         spot_market_vault: ctx.accounts.spot_market_vault.to_account_info(),
-        user_token_account: ctx.accounts.user_vault_token_account.to_account_info(), // TODO: this is the pda for the user token account
+        user_token_account: ctx.accounts.user_vault_token_account.to_account_info(),
         token_program: ctx.accounts.token_program.to_account_info(),
     };
 
@@ -99,21 +218,40 @@ pub fn handle_drift_deposit<'c: 'info, 'info>(
     // cpi_ctx = cpi_ctx.with_remaining_accounts(remaining_accounts);
     drift::cpi::deposit(cpi_ctx, market_index, amount, false)?;
 
+    // Token-2022 transfer fees are withheld from what reaches the protocol
+    let received = amount
+        .checked_sub(transfer_fee(
+            &ctx.accounts.token_vault_mint.to_account_info(),
+            amount,
+        )?)
+        .ok_or(VaultError::Underflow)?;
+
     // Update the users position data.
     let user_state = &mut ctx.accounts.user_state;
-    let user_token_vault = &mut ctx.accounts.user_token_vault;
+    let user_token_vault = &ctx.accounts.user_token_vault;
 
-    update_user_position(
+    let post_balance = update_user_position(
         user_state,
-        user_token_vault,
         user_token_vault.key(),
         ctx.accounts.user_vault_token_account.key(),
         ctx.accounts.drift_program.key(),
         ctx.accounts.spot_market_vault.key(),
         vault_index,
-        amount,
+        sub_account_id,
+        market_index,
+        PositionKind::Lending,
+        received,
         false,
     )?;
 
+    emit_cpi!(ProtocolDeposited {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol: ctx.accounts.drift_program.key(),
+        protocol_vault: ctx.accounts.spot_market_vault.key(),
+        amount: received,
+        post_balance,
+    });
+
     Ok(())
 }
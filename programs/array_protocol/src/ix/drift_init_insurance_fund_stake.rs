@@ -0,0 +1,98 @@
+use crate::controller::{
+    drift_insurance_fund_stake_address, drift_spot_market_address, drift_state_address,
+    drift_user_stats_address, ensure_not_paused, is_authorized, UserAction, VaultError,
+};
+use crate::events::ProtocolAccountInitialized;
+use crate::state::{ProgramState, User, PAUSE_PROTOCOLS};
+use crate::{drift, get_user_seeds};
+use anchor_lang::prelude::*;
+use drift::program::Drift;
+
+/// Accounts for `init_drift_insurance_fund_stake`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(market_index: u16)]
+pub struct InitDriftInsuranceFundStake<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Drift) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    /// CHECK: Drift's state account, checked against its derived address
+    #[account(address = drift_state_address() @ VaultError::InvalidDriftAccount)]
+    pub drift_state: AccountInfo<'info>,
+
+    /// CHECK: Drift spot market of `market_index`, checked against its derived address
+    #[account(address = drift_spot_market_address(market_index) @ VaultError::DriftMarketMismatch)]
+    pub spot_market: AccountInfo<'info>,
+
+    /// CHECK: Drift insurance fund stake of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_insurance_fund_stake_address(&user_state.key(), market_index) @ VaultError::InvalidDriftAccount
+    )]
+    pub insurance_fund_stake: AccountInfo<'info>,
+
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
+    pub drift_user_stats: AccountInfo<'info>,
+
+    /// Drift Program
+    pub drift_program: Program<'info, Drift>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for `init_drift_insurance_fund_stake`.
+/// Creates the user's stake account in the insurance fund of `market_index`, the signer pays the
+/// rent. The Drift user stats must exist.
+pub fn handle_init_drift_insurance_fund_stake(
+    ctx: Context<InitDriftInsuranceFundStake>,
+    market_index: u16,
+) -> Result<()> {
+    ensure_not_paused(&ctx.accounts.state, None, PAUSE_PROTOCOLS)?;
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.drift_program.to_account_info(),
+        drift::cpi::accounts::InitializeInsuranceFundStake {
+            spot_market: ctx.accounts.spot_market.to_account_info(),
+            insurance_fund_stake: ctx.accounts.insurance_fund_stake.to_account_info(),
+            user_stats: ctx.accounts.drift_user_stats.to_account_info(),
+            state: ctx.accounts.drift_state.to_account_info(),
+            authority: ctx.accounts.user_state.to_account_info(),
+            payer: ctx.accounts.signer.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        signer_seeds,
+    );
+    drift::cpi::initialize_insurance_fund_stake(cpi_ctx, market_index)?;
+
+    emit_cpi!(ProtocolAccountInitialized {
+        user: ctx.accounts.user_state.key(),
+        protocol: ctx.accounts.drift_program.key(),
+        account: ctx.accounts.insurance_fund_stake.key(),
+    });
+
+    Ok(())
+}
@@ -1,28 +1,51 @@
-use crate::state::User;
+use crate::controller::{
+    drift_delegate, drift_state_address, drift_user_address, drift_user_stats_address,
+    ensure_not_paused, is_authorized, UserAction, VaultError,
+};
+use crate::events::{DriftDelegateUpdated, ProtocolAccountInitialized};
+use crate::state::{ProgramState, User, PAUSE_PROTOCOLS};
 use crate::{drift, get_user_seeds};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::TokenInterface;
 use drift::program::Drift;
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(sub_account_id: u16)]
 pub struct InitDriftUser<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Drift) @ VaultError::UnauthorizedUser
+    )]
     pub user_state: Account<'info, User>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    /// CHECK: Drift's state account, checked against its derived address
+    #[account(mut, address = drift_state_address() @ VaultError::InvalidDriftAccount)]
     pub drift_state: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: Drift user of `user_state`'s sub-account, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_address(&user_state.key(), sub_account_id) @ VaultError::InvalidDriftAccount
+    )]
     pub drift_user: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
     pub drift_user_stats: AccountInfo<'info>,
 
     /// CHECK: program signer
@@ -36,6 +59,8 @@ pub struct InitDriftUser<'info> {
 }
 
 pub fn handle_init_drift_user(ctx: Context<InitDriftUser>, sub_account_id: u16) -> Result<()> {
+    ensure_not_paused(&ctx.accounts.state, None, PAUSE_PROTOCOLS)?;
+
     let drift_program = &ctx.accounts.drift_program;
 
     let seeds = get_user_seeds(
@@ -68,5 +93,31 @@ pub fn handle_init_drift_user(ctx: Context<InitDriftUser>, sub_account_id: u16)
 
     drift::cpi::initialize_user(init_ctx, sub_account_id, [0u8; 32])?;
 
+    emit_cpi!(ProtocolAccountInitialized {
+        user: ctx.accounts.user_state.key(),
+        protocol: ctx.accounts.drift_program.key(),
+        account: ctx.accounts.drift_user.key(),
+    });
+
+    // New Drift users start without a delegate, give them the current one
+    let delegate = drift_delegate(&ctx.accounts.user_state);
+    if delegate != Pubkey::default() {
+        let delegate_ctx = CpiContext::new_with_signer(
+            drift_program.to_account_info(),
+            drift::cpi::accounts::UpdateUserDelegate {
+                user: ctx.accounts.drift_user.to_account_info(),
+                authority: ctx.accounts.user_state.to_account_info(),
+            },
+            signer_seeds,
+        );
+        drift::cpi::update_user_delegate(delegate_ctx, sub_account_id, delegate)?;
+
+        emit_cpi!(DriftDelegateUpdated {
+            user: ctx.accounts.user_state.key(),
+            drift_user: ctx.accounts.drift_user.key(),
+            delegate,
+        });
+    }
+
     Ok(())
 }
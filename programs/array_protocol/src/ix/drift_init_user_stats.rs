@@ -1,23 +1,43 @@
-use crate::state::User;
+use crate::controller::{
+    drift_state_address, drift_user_stats_address, ensure_not_paused, is_authorized, UserAction,
+    VaultError,
+};
+use crate::events::ProtocolAccountInitialized;
+use crate::state::{ProgramState, User, PAUSE_PROTOCOLS};
 use crate::{drift, get_user_seeds};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::TokenInterface;
 use drift::program::Drift;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitDriftUserStats<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Drift) @ VaultError::UnauthorizedUser
+    )]
     pub user_state: Account<'info, User>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    /// CHECK: Drift's state account, checked against its derived address
+    #[account(mut, address = drift_state_address() @ VaultError::InvalidDriftAccount)]
     pub drift_state: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
     pub drift_user_stats: AccountInfo<'info>,
 
     /// CHECK: program signer
@@ -31,6 +51,8 @@ pub struct InitDriftUserStats<'info> {
 }
 
 pub fn handle_init_drift_user_stats(ctx: Context<InitDriftUserStats>) -> Result<()> {
+    ensure_not_paused(&ctx.accounts.state, None, PAUSE_PROTOCOLS)?;
+
     let drift_program = &ctx.accounts.drift_program;
 
     let seeds = get_user_seeds(
@@ -62,5 +84,11 @@ pub fn handle_init_drift_user_stats(ctx: Context<InitDriftUserStats>) -> Result<
 
     drift::cpi::initialize_user_stats(init_stats_ctx)?;
 
+    emit_cpi!(ProtocolAccountInitialized {
+        user: ctx.accounts.user_state.key(),
+        protocol: ctx.accounts.drift_program.key(),
+        account: ctx.accounts.drift_user_stats.key(),
+    });
+
     Ok(())
 }
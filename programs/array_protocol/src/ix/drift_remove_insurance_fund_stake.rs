@@ -0,0 +1,200 @@
+use crate::controller::{
+    book_position_value, drift_insurance_fund_stake_address, drift_insurance_fund_vault_address,
+    drift_signer_address, drift_spot_market_address, drift_state_address, drift_user_stats_address,
+    ensure_not_paused, find_user_position, is_authorized, update_user_position, UserAction,
+    VaultError,
+};
+use crate::events::ProtocolWithdrawn;
+use crate::state::{
+    PositionKind, ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_PROTOCOLS,
+    PAUSE_WITHDRAWALS,
+};
+use crate::{drift, get_user_seeds};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use drift::program::Drift;
+
+/// Accounts for `remove_drift_insurance_fund_stake`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16, market_index: u16)]
+pub struct RemoveDriftInsuranceFundStake<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(owner = token_program.key())]
+    pub token_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Drift) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", &vault_index.to_le_bytes()],
+        bump
+    )]
+    pub token_vault: Box<Account<'info, SupportedTokenVault>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        token::mint = token_vault_mint,
+        token::authority = user_state,
+        token::token_program = token_program
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Box<Account<'info, UserTokenVault>>,
+
+    /// CHECK: Drift's state account, checked against its derived address
+    #[account(address = drift_state_address() @ VaultError::InvalidDriftAccount)]
+    pub drift_state: AccountInfo<'info>,
+
+    /// CHECK: Drift spot market of `market_index`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_spot_market_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
+    pub spot_market: AccountInfo<'info>,
+
+    /// CHECK: Drift insurance fund stake of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_insurance_fund_stake_address(&user_state.key(), market_index) @ VaultError::InvalidDriftAccount
+    )]
+    pub insurance_fund_stake: AccountInfo<'info>,
+
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
+    pub drift_user_stats: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        address = drift_insurance_fund_vault_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
+    pub insurance_fund_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Drift's signer PDA, checked against its derived address
+    #[account(address = drift_signer_address() @ VaultError::InvalidDriftAccount)]
+    pub drift_signer: AccountInfo<'info>,
+
+    /// Drift Program
+    pub drift_program: Program<'info, Drift>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Handler for `remove_drift_insurance_fund_stake`.
+/// Once Drift's unstaking period has passed, pays the requested stake back into the user's vault
+/// and closes the `InsuranceFundUnstake` position. Drift pays less than requested if the fund
+/// lost value in the meantime. What arrives above or below the principal moves the user's share
+/// price.
+pub fn handle_remove_drift_insurance_fund_stake(
+    ctx: Context<RemoveDriftInsuranceFundStake>,
+    vault_index: u16,
+    market_index: u16,
+) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_WITHDRAWALS | PAUSE_PROTOCOLS,
+    )?;
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let balance_before = ctx.accounts.user_vault_token_account.amount;
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.drift_program.to_account_info(),
+        drift::cpi::accounts::RemoveInsuranceFundStake {
+            state: ctx.accounts.drift_state.to_account_info(),
+            spot_market: ctx.accounts.spot_market.to_account_info(),
+            insurance_fund_stake: ctx.accounts.insurance_fund_stake.to_account_info(),
+            user_stats: ctx.accounts.drift_user_stats.to_account_info(),
+            authority: ctx.accounts.user_state.to_account_info(),
+            insurance_fund_vault: ctx.accounts.insurance_fund_vault.to_account_info(),
+            drift_signer: ctx.accounts.drift_signer.to_account_info(),
+            user_token_account: ctx.accounts.user_vault_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+        signer_seeds,
+    );
+    drift::cpi::remove_insurance_fund_stake(cpi_ctx, market_index)?;
+
+    // Measure what actually arrived in the user's vault
+    ctx.accounts.user_vault_token_account.reload()?;
+    let received = ctx
+        .accounts
+        .user_vault_token_account
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(VaultError::Underflow)?;
+
+    let protocol = ctx.accounts.drift_program.key();
+    let protocol_vault = ctx.accounts.insurance_fund_vault.key();
+    let idx = find_user_position(
+        &ctx.accounts.user_state,
+        vault_index,
+        protocol,
+        protocol_vault,
+        0,
+        PositionKind::InsuranceFundUnstake,
+    )?;
+    let principal = ctx.accounts.user_state.positions[idx].deposited_amount;
+
+    let post_balance = update_user_position(
+        &mut ctx.accounts.user_state,
+        ctx.accounts.user_token_vault.key(),
+        ctx.accounts.user_vault_token_account.key(),
+        protocol,
+        protocol_vault,
+        vault_index,
+        0,
+        market_index,
+        PositionKind::InsuranceFundUnstake,
+        principal,
+        true,
+    )?;
+
+    // Drift pays out the stake's share of the fund, the difference to the principal is yield or a
+    // loss of the user's
+    book_position_value(
+        &mut ctx.accounts.token_vault,
+        &mut ctx.accounts.user_token_vault,
+        principal,
+        received,
+    )?;
+
+    emit_cpi!(ProtocolWithdrawn {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol,
+        protocol_vault,
+        amount: received,
+        post_balance,
+    });
+
+    Ok(())
+}
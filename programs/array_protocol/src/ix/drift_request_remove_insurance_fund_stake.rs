@@ -0,0 +1,191 @@
+use crate::controller::{
+    drift_insurance_fund_stake_address, drift_insurance_fund_vault_address,
+    drift_spot_market_address, drift_user_stats_address, ensure_not_paused, find_user_position,
+    is_authorized, update_user_position, UserAction, VaultError,
+};
+use crate::events::InsuranceFundUnstakeRequested;
+use crate::state::{
+    PositionKind, ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_PROTOCOLS,
+    PAUSE_WITHDRAWALS,
+};
+use crate::{drift, get_user_seeds};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use drift::program::Drift;
+
+/// Accounts for `request_remove_drift_insurance_fund_stake`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16, market_index: u16)]
+pub struct RequestRemoveDriftInsuranceFundStake<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Drift) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(
+        seeds = [b"token_vault", &vault_index.to_le_bytes()],
+        bump
+    )]
+    pub token_vault: Box<Account<'info, SupportedTokenVault>>,
+
+    #[account(
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Box<Account<'info, UserTokenVault>>,
+
+    /// CHECK: Drift spot market of `market_index`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_spot_market_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
+    pub spot_market: AccountInfo<'info>,
+
+    /// CHECK: Drift insurance fund stake of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_insurance_fund_stake_address(&user_state.key(), market_index) @ VaultError::InvalidDriftAccount
+    )]
+    pub insurance_fund_stake: AccountInfo<'info>,
+
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
+    pub drift_user_stats: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        address = drift_insurance_fund_vault_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
+    pub insurance_fund_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Drift Program
+    pub drift_program: Program<'info, Drift>,
+}
+
+/// Handler for `request_remove_drift_insurance_fund_stake`.
+/// Starts Drift's unstaking period for `amount` of the stake. The principal behind it moves from
+/// the `InsuranceFundStake` position to an `InsuranceFundUnstake` position stamped with the
+/// current slot, until `remove_drift_insurance_fund_stake` pays it out.
+pub fn handle_request_remove_drift_insurance_fund_stake(
+    ctx: Context<RequestRemoveDriftInsuranceFundStake>,
+    vault_index: u16,
+    market_index: u16,
+    amount: u64,
+) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_WITHDRAWALS | PAUSE_PROTOCOLS,
+    )?;
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.drift_program.to_account_info(),
+        drift::cpi::accounts::RequestRemoveInsuranceFundStake {
+            spot_market: ctx.accounts.spot_market.to_account_info(),
+            insurance_fund_stake: ctx.accounts.insurance_fund_stake.to_account_info(),
+            user_stats: ctx.accounts.drift_user_stats.to_account_info(),
+            authority: ctx.accounts.user_state.to_account_info(),
+            insurance_fund_vault: ctx.accounts.insurance_fund_vault.to_account_info(),
+        },
+        signer_seeds,
+    );
+    drift::cpi::request_remove_insurance_fund_stake(cpi_ctx, market_index, amount)?;
+
+    let user_token_vault_key = ctx.accounts.user_token_vault.key();
+    let user_vault_token_account_key = ctx.accounts.user_vault_token_account.key();
+    let protocol = ctx.accounts.drift_program.key();
+    let protocol_vault = ctx.accounts.insurance_fund_vault.key();
+
+    // Earnings on top of the staked principal are not part of the position.
+    let idx = find_user_position(
+        &ctx.accounts.user_state,
+        vault_index,
+        protocol,
+        protocol_vault,
+        0,
+        PositionKind::InsuranceFundStake,
+    )?;
+    let principal = amount.min(ctx.accounts.user_state.positions[idx].deposited_amount);
+
+    update_user_position(
+        &mut ctx.accounts.user_state,
+        user_token_vault_key,
+        user_vault_token_account_key,
+        protocol,
+        protocol_vault,
+        vault_index,
+        0,
+        market_index,
+        PositionKind::InsuranceFundStake,
+        principal,
+        true,
+    )?;
+    update_user_position(
+        &mut ctx.accounts.user_state,
+        user_token_vault_key,
+        user_vault_token_account_key,
+        protocol,
+        protocol_vault,
+        vault_index,
+        0,
+        market_index,
+        PositionKind::InsuranceFundUnstake,
+        principal,
+        false,
+    )?;
+
+    let slot = Clock::get()?.slot;
+    let idx = find_user_position(
+        &ctx.accounts.user_state,
+        vault_index,
+        protocol,
+        protocol_vault,
+        0,
+        PositionKind::InsuranceFundUnstake,
+    )?;
+    ctx.accounts.user_state.positions[idx].last_synced_slot = slot;
+
+    msg!(
+        "Requested removal of {} from insurance fund {}",
+        amount,
+        market_index
+    );
+
+    emit_cpi!(InsuranceFundUnstakeRequested {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        market_index,
+        amount,
+        principal,
+        slot,
+    });
+
+    Ok(())
+}
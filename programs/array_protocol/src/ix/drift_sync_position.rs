@@ -0,0 +1,173 @@
+use crate::controller::{
+    book_position_value, drift_user_sub_account_id, ensure_not_paused, find_user_position,
+    VaultError, DRIFT_USER_SUB_ACCOUNT_ID_OFFSET,
+};
+use crate::drift;
+use crate::events::PositionUpdated;
+use crate::state::{
+    PositionKind, ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_PROTOCOLS,
+};
+use anchor_lang::prelude::*;
+use drift::accounts::SpotMarket;
+use drift::program::Drift;
+use drift::types::{SpotBalanceType, SpotPosition};
+
+/// Byte offsets into the Drift `User` account. It is too large to deserialize on the stack, so
+/// only the authority and the spot position array are read.
+const DRIFT_USER_AUTHORITY_OFFSET: usize = 8;
+const DRIFT_USER_SPOT_POSITIONS_OFFSET: usize = 104;
+const DRIFT_USER_SPOT_POSITION_SIZE: usize = 40;
+const DRIFT_USER_SPOT_POSITION_COUNT: usize = 8;
+
+/// Precision of `SpotMarket.cumulative_deposit_interest` (1e10) times the precision of
+/// `SpotPosition.scaled_balance` (1e9).
+const DRIFT_SPOT_BALANCE_DECIMALS: u32 = 19;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct SyncDriftPosition<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", &vault_index.to_le_bytes()],
+        bump
+    )]
+    pub token_vault: Box<Account<'info, SupportedTokenVault>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Box<Account<'info, UserTokenVault>>,
+
+    /// CHECK: owner and authority are checked in the handler
+    #[account(owner = drift_program.key())]
+    pub drift_user: AccountInfo<'info>,
+
+    /// CHECK: owner is checked, the discriminator is checked on deserialization
+    #[account(owner = drift_program.key())]
+    pub spot_market: AccountInfo<'info>,
+
+    /// Drift Program
+    pub drift_program: Program<'info, Drift>,
+}
+
+/// Reads the user's Drift spot balance for the position's market and stores its token value. The
+/// position is picked by the sub-account stored in `drift_user`. The change in value since the
+/// last sync is booked into the user's share price.
+/// Anyone may call this, it only refreshes bookkeeping.
+pub fn handle_sync_drift_position(ctx: Context<SyncDriftPosition>, vault_index: u16) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_PROTOCOLS,
+    )?;
+
+    let spot_market =
+        SpotMarket::try_deserialize(&mut &ctx.accounts.spot_market.data.borrow()[..])?;
+
+    let (sub_account_id, scaled_balance) = {
+        let data = ctx.accounts.drift_user.data.borrow();
+        require_gte!(
+            data.len(),
+            DRIFT_USER_SUB_ACCOUNT_ID_OFFSET + 2,
+            VaultError::InvalidProtocolAccount
+        );
+
+        let authority = Pubkey::try_from_slice(
+            &data[DRIFT_USER_AUTHORITY_OFFSET..DRIFT_USER_AUTHORITY_OFFSET + 32],
+        )?;
+        require_keys_eq!(
+            authority,
+            ctx.accounts.user_state.key(),
+            VaultError::InvalidProtocolAccount
+        );
+
+        let sub_account_id = drift_user_sub_account_id(&data)?;
+
+        let mut scaled_balance = 0u64;
+        for i in 0..DRIFT_USER_SPOT_POSITION_COUNT {
+            let start = DRIFT_USER_SPOT_POSITIONS_OFFSET + i * DRIFT_USER_SPOT_POSITION_SIZE;
+            let spot_position = SpotPosition::deserialize(
+                &mut &data[start..start + DRIFT_USER_SPOT_POSITION_SIZE],
+            )?;
+            if spot_position.market_index == spot_market.market_index
+                && spot_position.scaled_balance > 0
+                && matches!(spot_position.balance_type, SpotBalanceType::Deposit)
+            {
+                scaled_balance = spot_position.scaled_balance;
+                break;
+            }
+        }
+        (sub_account_id, scaled_balance)
+    };
+
+    // Drift rounds deposit balances down, mirror it so the value is never overstated
+    let precision_decrease = 10u128
+        .checked_pow(DRIFT_SPOT_BALANCE_DECIMALS.saturating_sub(spot_market.decimals))
+        .ok_or(VaultError::Overflow)?;
+    let value = (scaled_balance as u128)
+        .checked_mul(spot_market.cumulative_deposit_interest)
+        .ok_or(VaultError::Overflow)?
+        / precision_decrease;
+    let value = u64::try_from(value).map_err(|_| VaultError::Overflow)?;
+
+    let user_state = &mut ctx.accounts.user_state;
+    let idx = find_user_position(
+        user_state,
+        vault_index,
+        ctx.accounts.drift_program.key(),
+        spot_market.vault,
+        sub_account_id,
+        PositionKind::Lending,
+    )?;
+    let slot = Clock::get()?.slot;
+
+    let pos = &mut user_state.positions[idx];
+    let old_value = pos.value;
+    pos.value = value;
+    pos.last_synced_slot = slot;
+    let deposited_amount = pos.deposited_amount;
+
+    book_position_value(
+        &mut ctx.accounts.token_vault,
+        &mut ctx.accounts.user_token_vault,
+        old_value,
+        value,
+    )?;
+
+    msg!(
+        "Synced Drift position {} market {} sub-account {}: value {} (deposited {})",
+        idx,
+        spot_market.market_index,
+        sub_account_id,
+        value,
+        deposited_amount
+    );
+    emit_cpi!(PositionUpdated {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol: ctx.accounts.drift_program.key(),
+        protocol_vault: spot_market.vault,
+        value,
+        deposited_amount,
+        user_balance: ctx.accounts.user_token_vault.balance,
+        slot,
+    });
+
+    Ok(())
+}
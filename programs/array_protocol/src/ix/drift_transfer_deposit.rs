@@ -0,0 +1,224 @@
+use crate::controller::{
+    drift_spot_market_vault_address, drift_state_address, drift_user_address,
+    drift_user_stats_address, ensure_not_paused, ensure_within_position, find_user_position,
+    is_authorized, update_user_position, UserAction, VaultError,
+};
+use crate::events::{ProtocolDeposited, ProtocolWithdrawn};
+use crate::state::{
+    PositionKind, ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_PROTOCOLS,
+};
+use crate::{drift, get_user_seeds};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use drift::program::Drift;
+
+/// Accounts for `transfer_drift_deposit`.
+/// Drift's remaining accounts (oracles, spot markets) are passed as remaining accounts.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16, from_sub_account_id: u16, to_sub_account_id: u16, market_index: u16)]
+pub struct TransferDriftDeposit<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Drift) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(
+        seeds = [b"token_vault", &vault_index.to_le_bytes()],
+        bump
+    )]
+    pub token_vault: Box<Account<'info, SupportedTokenVault>>,
+
+    #[account(
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Box<Account<'info, UserTokenVault>>,
+
+    /// CHECK: Drift's state account, checked against its derived address
+    #[account(address = drift_state_address() @ VaultError::InvalidDriftAccount)]
+    pub drift_state: AccountInfo<'info>,
+
+    /// CHECK: Drift user of the source sub-account, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_address(&user_state.key(), from_sub_account_id) @ VaultError::InvalidDriftAccount
+    )]
+    pub from_drift_user: AccountInfo<'info>,
+
+    /// CHECK: Drift user of the destination sub-account, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_address(&user_state.key(), to_sub_account_id) @ VaultError::InvalidDriftAccount
+    )]
+    pub to_drift_user: AccountInfo<'info>,
+
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
+    pub drift_user_stats: AccountInfo<'info>,
+
+    #[account(
+        address = drift_spot_market_vault_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
+    pub spot_market_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Drift Program
+    pub drift_program: Program<'info, Drift>,
+}
+
+/// Handler for `transfer_drift_deposit`.
+/// Moves `amount` of a spot deposit between two of the user's Drift sub-accounts and moves the
+/// position with it. `amount` is capped at what the source position holds, its principal share
+/// and synced interest carry over to the destination.
+pub fn handle_transfer_drift_deposit<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, TransferDriftDeposit<'info>>,
+    vault_index: u16,
+    from_sub_account_id: u16,
+    to_sub_account_id: u16,
+    market_index: u16,
+    amount: u64,
+) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_PROTOCOLS,
+    )?;
+    require_neq!(
+        from_sub_account_id,
+        to_sub_account_id,
+        VaultError::SameSubAccount
+    );
+
+    let user_token_vault_key = ctx.accounts.user_token_vault.key();
+    let user_vault_token_account_key = ctx.accounts.user_vault_token_account.key();
+    let protocol = ctx.accounts.drift_program.key();
+    let protocol_vault = ctx.accounts.spot_market_vault.key();
+
+    // Only what the source position holds may move, anything above it would be a Drift borrow
+    let from_idx = find_user_position(
+        &ctx.accounts.user_state,
+        vault_index,
+        protocol,
+        protocol_vault,
+        from_sub_account_id,
+        PositionKind::Lending,
+    )?;
+    ensure_within_position(&ctx.accounts.user_state, from_idx, amount)?;
+    let principal = amount.min(ctx.accounts.user_state.positions[from_idx].deposited_amount);
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = drift::cpi::accounts::TransferDeposit {
+        from_user: ctx.accounts.from_drift_user.to_account_info(),
+        to_user: ctx.accounts.to_drift_user.to_account_info(),
+        user_stats: ctx.accounts.drift_user_stats.to_account_info(),
+        authority: ctx.accounts.user_state.to_account_info(),
+        state: ctx.accounts.drift_state.to_account_info(),
+        spot_market_vault: ctx.accounts.spot_market_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.drift_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    drift::cpi::transfer_deposit(cpi_ctx, market_index, amount)?;
+
+    // The tokens never leave Drift, so the principal and the synced interest move between the
+    // positions as they are and nothing is booked into the user's share price.
+    let interest = amount - principal;
+    let post_balance = update_user_position(
+        &mut ctx.accounts.user_state,
+        user_token_vault_key,
+        user_vault_token_account_key,
+        protocol,
+        protocol_vault,
+        vault_index,
+        from_sub_account_id,
+        market_index,
+        PositionKind::Lending,
+        principal,
+        true,
+    )?;
+    let from_pos = &mut ctx.accounts.user_state.positions[from_idx];
+    from_pos.value = from_pos.value.saturating_sub(interest);
+
+    emit_cpi!(ProtocolWithdrawn {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol,
+        protocol_vault,
+        amount,
+        post_balance,
+    });
+
+    let post_balance = update_user_position(
+        &mut ctx.accounts.user_state,
+        user_token_vault_key,
+        user_vault_token_account_key,
+        protocol,
+        protocol_vault,
+        vault_index,
+        to_sub_account_id,
+        market_index,
+        PositionKind::Lending,
+        principal,
+        false,
+    )?;
+    let to_idx = find_user_position(
+        &ctx.accounts.user_state,
+        vault_index,
+        protocol,
+        protocol_vault,
+        to_sub_account_id,
+        PositionKind::Lending,
+    )?;
+    let to_pos = &mut ctx.accounts.user_state.positions[to_idx];
+    to_pos.value = to_pos
+        .value
+        .checked_add(interest)
+        .ok_or(VaultError::Overflow)?;
+
+    emit_cpi!(ProtocolDeposited {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol,
+        protocol_vault,
+        amount,
+        post_balance,
+    });
+
+    msg!(
+        "Transferred {} of market {} from sub-account {} to {}",
+        amount,
+        market_index,
+        from_sub_account_id,
+        to_sub_account_id
+    );
+
+    Ok(())
+}
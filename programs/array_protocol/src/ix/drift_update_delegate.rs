@@ -0,0 +1,76 @@
+use crate::controller::{drift_delegate, drift_user_address, ensure_not_paused, VaultError};
+use crate::events::DriftDelegateUpdated;
+use crate::state::{ProgramState, User, PAUSE_PROTOCOLS};
+use crate::{drift, get_user_seeds};
+use anchor_lang::prelude::*;
+use drift::program::Drift;
+
+/// Accounts for `update_drift_delegate`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(sub_account_id: u16)]
+pub struct UpdateDriftDelegate<'info> {
+    #[account(
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    /// CHECK: Drift user of `user_state`'s sub-account, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_address(&user_state.key(), sub_account_id) @ VaultError::InvalidDriftAccount
+    )]
+    pub drift_user: AccountInfo<'info>,
+
+    /// Drift Program
+    pub drift_program: Program<'info, Drift>,
+}
+
+/// Handler for `update_drift_delegate`.
+/// Copies the user's delegate onto the Drift user of `sub_account_id`, or clears it when the
+/// delegate lacks the Drift permission. Anyone may call this, it only mirrors the Array state.
+pub fn handle_update_drift_delegate(
+    ctx: Context<UpdateDriftDelegate>,
+    sub_account_id: u16,
+) -> Result<()> {
+    ensure_not_paused(&ctx.accounts.state, None, PAUSE_PROTOCOLS)?;
+
+    let delegate = drift_delegate(&ctx.accounts.user_state);
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.drift_program.to_account_info(),
+        drift::cpi::accounts::UpdateUserDelegate {
+            user: ctx.accounts.drift_user.to_account_info(),
+            authority: ctx.accounts.user_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    drift::cpi::update_user_delegate(cpi_ctx, sub_account_id, delegate)?;
+
+    msg!(
+        "Drift sub-account {} delegate set to {}",
+        sub_account_id,
+        delegate
+    );
+
+    emit_cpi!(DriftDelegateUpdated {
+        user: ctx.accounts.user_state.key(),
+        drift_user: ctx.accounts.drift_user.key(),
+        delegate,
+    });
+
+    Ok(())
+}
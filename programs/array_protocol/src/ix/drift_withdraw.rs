@@ -1,12 +1,22 @@
-use crate::controller::update_user_position;
-use crate::state::{SupportedTokenVault, User, UserTokenVault};
+use crate::controller::{
+    book_withdrawn_interest, drift_signer_address, drift_spot_market_vault_address,
+    drift_state_address, drift_user_address, drift_user_stats_address, ensure_not_paused,
+    ensure_within_position, find_user_position, is_authorized, update_user_position, UserAction,
+    VaultError,
+};
+use crate::events::ProtocolWithdrawn;
+use crate::state::{
+    PositionKind, ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_PROTOCOLS,
+    PAUSE_WITHDRAWALS,
+};
 use crate::{drift, get_user_seeds};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use drift::program::Drift;
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(vault_index: u16)]
+#[instruction(vault_index: u16, sub_account_id: u16, market_index: u16)]
 pub struct DriftWithdraw<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -14,9 +24,20 @@ pub struct DriftWithdraw<'info> {
     #[account(owner = token_program.key())]
     pub token_vault_mint: InterfaceAccount<'info, Mint>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Drift) @ VaultError::UnauthorizedUser
+    )]
     pub user_state: Account<'info, User>,
 
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
     #[account(
         mut,
         seeds = [b"token_vault", &vault_index.to_le_bytes()],
@@ -41,23 +62,32 @@ pub struct DriftWithdraw<'info> {
     )]
     pub user_token_vault: Account<'info, UserTokenVault>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: Drift's state account, checked against its derived address
+    #[account(mut, address = drift_state_address() @ VaultError::InvalidDriftAccount)]
     pub drift_state: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: Drift user of `user_state`'s sub-account, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_address(&user_state.key(), sub_account_id) @ VaultError::InvalidDriftAccount
+    )]
     pub drift_user: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount
+    )]
     pub drift_user_stats: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    #[account(
+        mut,
+        address = drift_spot_market_vault_address(market_index) @ VaultError::DriftMarketMismatch
+    )]
     pub spot_market_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// CHECK: forced drift_signer
+    /// CHECK: Drift's signer PDA, checked against its derived address
+    #[account(address = drift_signer_address() @ VaultError::InvalidDriftAccount)]
     pub drift_signer: AccountInfo<'info>,
 
     /// CHECK: program signer
@@ -73,9 +103,16 @@ pub struct DriftWithdraw<'info> {
 pub fn handle_drift_withdraw<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, DriftWithdraw<'info>>,
     vault_index: u16,
+    sub_account_id: u16,
     market_index: u16,
     amount: u64,
 ) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_WITHDRAWALS | PAUSE_PROTOCOLS,
+    )?;
+
     let drift_program = ctx.accounts.drift_program.to_account_info();
 
     let seeds = get_user_seeds(
@@ -100,24 +137,57 @@ pub fn handle_drift_withdraw<'c: 'info, 'info>(
     let cpi_ctx = CpiContext::new_with_signer(drift_program, cpi_accounts, signer_seeds)
         .with_remaining_accounts(ctx.remaining_accounts.to_vec());
 
+    // Withdrawals are capped at what the position holds, so nothing borrowed from Drift is booked
+    // as interest.
+    let protocol = ctx.accounts.drift_program.key();
+    let protocol_vault = ctx.accounts.spot_market_vault.key();
+    let idx = find_user_position(
+        &ctx.accounts.user_state,
+        vault_index,
+        protocol,
+        protocol_vault,
+        sub_account_id,
+        PositionKind::Lending,
+    )?;
+    ensure_within_position(&ctx.accounts.user_state, idx, amount)?;
+    let principal = amount.min(ctx.accounts.user_state.positions[idx].deposited_amount);
+
     // cpi_ctx = cpi_ctx.with_remaining_accounts(remaining_accounts);
-    drift::cpi::withdraw(cpi_ctx, market_index, amount, false)?;
+    drift::cpi::withdraw(cpi_ctx, market_index, amount, true)?;
 
-    // Update the users position data.
-    let user_state = &mut ctx.accounts.user_state;
-    let user_token_vault = &mut ctx.accounts.user_token_vault;
+    // Interest earned on top of the principal is not part of the position, it is booked into the
+    // user's share price instead.
 
-    update_user_position(
-        user_state,
-        user_token_vault,
-        user_token_vault.key(),
+    // Update the users position data.
+    let post_balance = update_user_position(
+        &mut ctx.accounts.user_state,
+        ctx.accounts.user_token_vault.key(),
         ctx.accounts.user_vault_token_account.key(),
-        ctx.accounts.drift_program.key(),
-        ctx.accounts.spot_market_vault.key(),
+        protocol,
+        protocol_vault,
         vault_index,
-        amount,
+        sub_account_id,
+        market_index,
+        PositionKind::Lending,
+        principal,
         true,
     )?;
+    book_withdrawn_interest(
+        &mut ctx.accounts.user_state,
+        idx,
+        &mut ctx.accounts.token_vault,
+        &mut ctx.accounts.user_token_vault,
+        amount - principal,
+    )?;
+
+    emit_cpi!(ProtocolWithdrawn {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol,
+        protocol_vault,
+        amount,
+        post_balance,
+    });
 
     Ok(())
 }
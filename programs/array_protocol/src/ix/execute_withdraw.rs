@@ -0,0 +1,123 @@
+use crate::controller::{
+    accrue_management_fee, charge_performance_fee, is_authorized, unlock_shares, withdraw_shares,
+    UserAction, VaultError,
+};
+use crate::events::Withdrawn;
+use crate::get_user_seeds;
+use crate::state::{SupportedTokenVault, User, UserTokenVault, WithdrawalRequest};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+/// Accounts for `execute_withdraw`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct ExecuteWithdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(owner = token_program.key())]
+    pub token_vault_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Transfer) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        mut,
+        token::authority = signer
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        token::mint = token_vault_mint,
+        token::authority = user_state,
+        token::token_program = token_program
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Account<'info, UserTokenVault>,
+
+    /// TokenVault metadata
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub protocol_token_vault: Account<'info, SupportedTokenVault>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"withdrawal_request".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Handler for `execute_withdraw`.
+/// Once the delay has passed, releases the locked shares and withdraws the requested amount at
+/// the current share price, charging fees as `withdraw_spl` does. The tokens must be idle in the
+/// user's vault by then. Never checks the pause flags.
+pub fn handle_execute_withdraw(ctx: Context<ExecuteWithdraw>, vault_index: u16) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let request = &ctx.accounts.withdrawal_request;
+    require_gte!(now, request.executable_at, VaultError::WithdrawalNotReady);
+    let amount = request.amount;
+
+    // 1) Return the locked shares, then charge fees and burn the shares backing the amount
+    let vault = &mut ctx.accounts.protocol_token_vault;
+    let user_token_vault = &mut ctx.accounts.user_token_vault;
+    unlock_shares(user_token_vault, request.shares, request.principal, amount)?;
+    accrue_management_fee(vault, user_token_vault, now)?;
+    let (shares, principal) = withdraw_shares(vault, user_token_vault, amount)?;
+    let fee = charge_performance_fee(vault, user_token_vault, amount, principal)?;
+    msg!("Withdrew {} for {} shares, fee {}", amount, shares, fee);
+
+    // 2) Transfer from vault -> user, the fee stays behind until collected
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            mint: ctx.accounts.token_vault_mint.to_account_info(),
+            from: ctx.accounts.user_vault_token_account.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: ctx.accounts.user_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    anchor_spl::token_interface::transfer_checked(
+        cpi_ctx,
+        amount - fee,
+        ctx.accounts.token_vault_mint.decimals,
+    )?;
+
+    emit_cpi!(Withdrawn {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        amount,
+        fee,
+        shares,
+        post_balance: ctx.accounts.user_token_vault.balance,
+    });
+
+    Ok(())
+}
@@ -1,11 +1,15 @@
+use crate::events::ProgramInitialized;
 use crate::ids::admin_hot_wallet;
 use crate::state::ProgramState;
 use crate::state::Size;
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::TokenInterface;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Initialize<'info> {
+    /// Bootstrap admin. Only the one-time initialization is tied to `admin_hot_wallet`, every
+    /// other admin instruction checks `ProgramState.admin`.
     #[account(
         mut,
         constraint = admin.key() == admin_hot_wallet::id()
@@ -35,5 +39,11 @@ pub fn handle_init_program_state(ctx: Context<Initialize>) -> Result<()> {
     program_state.signer_pda = array_signer_pda;
     program_state.token_vault_count = 0;
     program_state.bump = bump;
+
+    emit_cpi!(ProgramInitialized {
+        admin: ctx.accounts.admin.key(),
+        signer_pda: array_signer_pda,
+    });
+
     Ok(())
 }
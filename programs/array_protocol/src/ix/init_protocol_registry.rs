@@ -0,0 +1,51 @@
+use crate::controller::VaultError;
+use crate::events::RegistryInitialized;
+use crate::state::{ProgramState, ProtocolRegistry, Size};
+use anchor_lang::prelude::*;
+
+/// Accounts for `init_protocol_registry`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct InitProtocolRegistry<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin,
+        constraint = vault_index < state.token_vault_count @ VaultError::InvalidVaultIndex
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolRegistry::SIZE,
+        seeds = [b"protocol_registry".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub registry: Box<Account<'info, ProtocolRegistry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for `init_protocol_registry`.
+/// Creates the empty registry of a vault initialized before registries existed. Vaults created
+/// since get theirs from `init_token_vault`.
+pub fn handle_init_protocol_registry(
+    ctx: Context<InitProtocolRegistry>,
+    vault_index: u16,
+) -> Result<()> {
+    ctx.accounts.registry.token_vault_index = vault_index;
+
+    msg!("Initialized protocol registry for vault {}", vault_index);
+
+    emit_cpi!(RegistryInitialized {
+        vault_index,
+        registry: ctx.accounts.registry.key(),
+    });
+
+    Ok(())
+}
@@ -1,22 +1,25 @@
-use crate::ids::admin_hot_wallet;
-use crate::state::{ProgramState, Size, SupportedTokenVault};
+use crate::controller::{ensure_supported_mint, VaultError};
+use crate::events::VaultInitialized;
+use crate::state::{ProgramState, ProtocolRegistry, Size, SupportedTokenVault};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenInterface};
 
 /// Accounts for `init_token_vault`.
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitTokenVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
     #[account(
         mut,
-        constraint = admin.key() == admin_hot_wallet::id()
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin
     )]
-    pub admin: Signer<'info>,
-
-    #[account(mut)]
     pub state: Account<'info, ProgramState>,
 
-    // #[account(owner = token_program.key())]
-    #[account()]
+    #[account(owner = token_program.key())]
     pub token_vault_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// TokenVault metadata
@@ -29,6 +32,16 @@ pub struct InitTokenVault<'info> {
     )]
     pub token_vault: Account<'info, SupportedTokenVault>,
 
+    /// Markets the vault may deposit into, empty until the admin registers some
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolRegistry::SIZE,
+        seeds = [b"protocol_registry".as_ref(), state.token_vault_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub registry: Box<Account<'info, ProtocolRegistry>>,
+
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
@@ -45,6 +58,8 @@ pub fn handle_init_token_vault(ctx: Context<InitTokenVault>) -> Result<()> {
     msg!("System Program: {}", ctx.accounts.system_program.key());
     msg!("Token Program: {}", ctx.accounts.token_program.key());
 
+    ensure_supported_mint(&ctx.accounts.token_vault_mint.to_account_info())?;
+
     let token_vault_count = ctx.accounts.state.token_vault_count;
 
     let vault_state = &mut ctx.accounts.token_vault;
@@ -52,8 +67,16 @@ pub fn handle_init_token_vault(ctx: Context<InitTokenVault>) -> Result<()> {
     vault_state.balance = 0;
     vault_state.token_vault_index = token_vault_count;
 
+    ctx.accounts.registry.token_vault_index = token_vault_count;
+
     let state = &mut ctx.accounts.state;
     state.token_vault_count += 1;
 
+    emit_cpi!(VaultInitialized {
+        user: Pubkey::default(),
+        vault_index: token_vault_count,
+        mint: ctx.accounts.token_vault_mint.key(),
+    });
+
     Ok(())
 }
@@ -1,8 +1,13 @@
-use crate::state::{Size, User};
+use crate::controller::ensure_not_paused;
+use crate::events::UserInitialized;
+use crate::state::{
+    ProgramState, User, DELEGATE_PERMISSION_ALL, INITIAL_POSITION_SLOTS, PAUSE_DEPOSITS,
+};
 use crate::ROBOT_PUBKEY;
 use anchor_lang::prelude::*;
 
 /// Accounts for the `create_user` instruction.
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitUser<'info> {
     #[account(mut)]
@@ -13,25 +18,38 @@ pub struct InitUser<'info> {
     #[account(
         init,
         payer = signer,
-        // Enough space for the user: 
-        // 8 (discriminator) + 1(bump) + 32(authority) + 32(delegate) + 8*(Position ~16 bytes) 
-        space = User::SIZE,
+        // Starts with `INITIAL_POSITION_SLOTS`, grown later with `add_user_positions`
+        space = User::space(INITIAL_POSITION_SLOTS),
         seeds = [b"user", signer.key().as_ref()],
         bump
     )]
     pub user_state: Account<'info, User>,
 
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
     pub system_program: Program<'info, System>,
 }
 
 /// Handler function for `create_user`.
 pub fn handle_init_user(ctx: Context<InitUser>) -> Result<()> {
+    ensure_not_paused(&ctx.accounts.state, None, PAUSE_DEPOSITS)?;
+
     let user_state = &mut ctx.accounts.user_state;
 
     user_state.authority = ctx.accounts.signer.key();
     user_state.delegate = ROBOT_PUBKEY;
-    user_state.positions = [Default::default(); 8];
+    user_state.delegate_permissions = DELEGATE_PERMISSION_ALL;
+    user_state.positions = vec![Default::default(); INITIAL_POSITION_SLOTS];
     user_state.bump = ctx.bumps.user_state;
 
+    emit_cpi!(UserInitialized {
+        user: ctx.accounts.user_state.key(),
+        authority: ctx.accounts.signer.key(),
+    });
+
     Ok(())
 }
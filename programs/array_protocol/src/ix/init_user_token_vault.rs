@@ -1,9 +1,11 @@
-use crate::state::{ProgramState, Size, SupportedTokenVault, User, UserTokenVault};
+use crate::controller::{ensure_not_paused, is_authorized, UserAction, VaultError};
+use crate::events::VaultInitialized;
+use crate::state::{ProgramState, Size, SupportedTokenVault, User, UserTokenVault, PAUSE_DEPOSITS};
 use anchor_lang::prelude::*;
-use anchor_spl::token::Token;
-use anchor_spl::token_interface::{Mint, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 /// Accounts for `init_token_vault`.
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(vault_index: u16)]
 pub struct InitUserTokenVault<'info> {
@@ -38,8 +40,12 @@ pub struct InitUserTokenVault<'info> {
     )]
     pub user_token_vault_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// CHECK: i am just testing
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::ManageAccount) @ VaultError::UnauthorizedUser
+    )]
     pub user_state: Box<Account<'info, User>>,
 
     #[account(mut)]
@@ -52,7 +58,7 @@ pub struct InitUserTokenVault<'info> {
     pub array_signer: AccountInfo<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
 }
 
@@ -62,6 +68,12 @@ pub fn handle_init_user_token_vault(
     ctx: Context<InitUserTokenVault>,
     vault_index: u16,
 ) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_DEPOSITS,
+    )?;
+
     msg!(
         "Initializing user token vault for vault index: {}",
         vault_index
@@ -72,5 +84,11 @@ pub fn handle_init_user_token_vault(
     user_token_vault.deposited_amount = 0;
     user_token_vault.token_vault_index = vault_index;
 
+    emit_cpi!(VaultInitialized {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        mint: ctx.accounts.token_vault.mint,
+    });
+
     Ok(())
 }
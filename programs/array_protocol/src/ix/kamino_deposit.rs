@@ -1,24 +1,44 @@
-use crate::controller::update_user_position;
+use crate::controller::{
+    ensure_not_paused, is_authorized, kamino_lending_market_authority_address,
+    kamino_obligation_address, transfer_fee, update_user_position, UserAction, VaultError,
+};
+use crate::events::ProtocolDeposited;
 use crate::get_user_seeds;
 use crate::klend::program::KaminoLending;
-use crate::state::{SupportedTokenVault, User, UserTokenVault};
+use crate::state::{
+    PositionKind, ProgramState, ProtocolRegistry, RegisteredMarket, SupportedTokenVault, User,
+    UserTokenVault, PAUSE_DEPOSITS, PAUSE_PROTOCOLS,
+};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::{instructions::Instructions, SysvarId};
+use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(vault_index: u16)]
+#[instruction(vault_index: u16, obligation_id: u8)]
 pub struct KaminoDeposit<'info> {
     // #[account(mut)]
     pub signer: Signer<'info>,
 
-    #[account(owner = token_program.key())]
-    //reserve_collateral_mint & reserve_liquidity_mint (they are the same)
+    /// reserve_liquidity_mint
+    #[account(mut, owner = token_program.key())]
     pub token_vault_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Kamino) @ VaultError::UnauthorizedUser
+    )]
     pub user_state: Box<Account<'info, User>>,
 
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
     #[account(
         mut,
         seeds = [b"token_vault", &vault_index.to_le_bytes()],
@@ -26,6 +46,12 @@ pub struct KaminoDeposit<'info> {
     )]
     pub token_vault: Box<Account<'info, SupportedTokenVault>>,
 
+    #[account(
+        seeds = [b"protocol_registry".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub registry: Box<Account<'info, ProtocolRegistry>>,
+
     #[account(
         mut,
         seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
@@ -43,15 +69,21 @@ pub struct KaminoDeposit<'info> {
     )]
     pub user_token_vault: Account<'info, UserTokenVault>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
+    /// CHECK: vanilla obligation `obligation_id` of `user_state` in `lending_market`, checked
+    /// against its derived address
+    #[account(
+        mut,
+        address = kamino_obligation_address(&user_state.key(), &lending_market.key(), obligation_id) @ VaultError::InvalidKaminoAccount
+    )]
     pub obligation: AccountInfo<'info>,
 
-    /// CHECK: target program handles
+    /// CHECK: registered with `reserve`, checked against the registry in the handler
     pub lending_market: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account()]
+    /// CHECK: authority of `lending_market`, checked against its derived address
+    #[account(
+        address = kamino_lending_market_authority_address(&lending_market.key()) @ VaultError::InvalidKaminoAccount
+    )]
     pub lending_market_authority: AccountInfo<'info>,
 
     /// CHECK: target program handles
@@ -61,6 +93,9 @@ pub struct KaminoDeposit<'info> {
     #[account(mut)]
     pub reserve_liquidity_supply: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(mut)]
+    pub reserve_collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(mut)]
     pub reserve_destination_deposit_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -68,27 +103,39 @@ pub struct KaminoDeposit<'info> {
     #[account(address = Instructions::id())]
     pub instruction_sysvar_account: AccountInfo<'info>,
 
-    /// CHECK: target program handles
-    #[account(mut)]
-    pub obligation_farm_user_state: AccountInfo<'info>,
-
-    /// CHECK: target program handles
-    #[account(mut)]
-    pub reserve_farm_state: AccountInfo<'info>,
-    // // pub farms_accounts: OptionalObligationFarmsAccounts<'info>,
-    // pub farms_program: UncheckedAccount<'info>,
-    /// Drift Program
+    /// Kamino Lending Program
     pub klend_program: Program<'info, KaminoLending>,
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program of the reserve collateral mint, klend always creates it with SPL Token
+    pub collateral_token_program: Program<'info, Token>,
 }
 
 pub fn handle_kamino_deposit<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, KaminoDeposit<'info>>,
     vault_index: u16,
+    _obligation_id: u8,
     amount: u64,
 ) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_DEPOSITS | PAUSE_PROTOCOLS,
+    )?;
+
+    require!(
+        ctx.accounts
+            .registry
+            .is_registered(&RegisteredMarket::kamino(
+                ctx.accounts.klend_program.key(),
+                ctx.accounts.reserve.key(),
+                ctx.accounts.lending_market.key(),
+            )),
+        VaultError::MarketNotRegistered
+    );
+
     let kamino_program = ctx.accounts.klend_program.to_account_info();
 
     let seeds = get_user_seeds(
@@ -106,14 +153,14 @@ pub fn handle_kamino_deposit<'c: 'info, 'info>(
             reserve: ctx.accounts.reserve.to_account_info(),
             reserve_liquidity_mint: ctx.accounts.token_vault_mint.to_account_info(),
             reserve_liquidity_supply: ctx.accounts.reserve_liquidity_supply.to_account_info(),
-            reserve_collateral_mint: ctx.accounts.token_vault_mint.to_account_info(),
+            reserve_collateral_mint: ctx.accounts.reserve_collateral_mint.to_account_info(),
             reserve_destination_deposit_collateral: ctx
                 .accounts
                 .reserve_destination_deposit_collateral
                 .to_account_info(),
             user_source_liquidity: ctx.accounts.user_vault_token_account.to_account_info(),
             placeholder_user_destination_collateral: None,
-            collateral_token_program: ctx.accounts.token_program.to_account_info(),
+            collateral_token_program: ctx.accounts.collateral_token_program.to_account_info(),
             liquidity_token_program: ctx.accounts.token_program.to_account_info(),
             instruction_sysvar_account: ctx.accounts.instruction_sysvar_account.to_account_info(),
         };
@@ -122,21 +169,40 @@ pub fn handle_kamino_deposit<'c: 'info, 'info>(
 
     crate::klend::cpi::deposit_reserve_liquidity_and_obligation_collateral(cpi_ctx, amount)?;
 
+    // Token-2022 transfer fees are withheld from what reaches the protocol
+    let received = amount
+        .checked_sub(transfer_fee(
+            &ctx.accounts.token_vault_mint.to_account_info(),
+            amount,
+        )?)
+        .ok_or(VaultError::Underflow)?;
+
     // Update the users position data.
     let user_state = &mut ctx.accounts.user_state;
-    let user_token_vault = &mut ctx.accounts.user_token_vault;
+    let user_token_vault = &ctx.accounts.user_token_vault;
 
-    update_user_position(
+    let post_balance = update_user_position(
         user_state,
-        user_token_vault,
         user_token_vault.key(),
         ctx.accounts.user_vault_token_account.key(),
         ctx.accounts.klend_program.key(),
         ctx.accounts.reserve.key(),
         vault_index,
-        amount,
+        0,
+        0,
+        PositionKind::Lending,
+        received,
         false,
     )?;
 
+    emit_cpi!(ProtocolDeposited {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol: ctx.accounts.klend_program.key(),
+        protocol_vault: ctx.accounts.reserve.key(),
+        amount: received,
+        post_balance,
+    });
+
     Ok(())
 }
@@ -0,0 +1,116 @@
+use crate::controller::{
+    ensure_not_paused, is_authorized, kamino_obligation_address, kamino_user_metadata_address,
+    UserAction, VaultError, KAMINO_VANILLA_OBLIGATION_TAG,
+};
+use crate::events::ProtocolAccountInitialized;
+use crate::get_user_seeds;
+use crate::klend::program::KaminoLending;
+use crate::klend::types::InitObligationArgs;
+use crate::state::{ProgramState, User, PAUSE_PROTOCOLS};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(obligation_id: u8)]
+pub struct InitKaminoObligation<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Kamino) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    /// CHECK: vanilla obligation `obligation_id` of `user_state` in `lending_market`, checked
+    /// against its derived address
+    #[account(
+        mut,
+        address = kamino_obligation_address(&user_state.key(), &lending_market.key(), obligation_id) @ VaultError::InvalidKaminoAccount
+    )]
+    pub obligation: AccountInfo<'info>,
+
+    /// CHECK: target program handles
+    pub lending_market: AccountInfo<'info>,
+
+    /// CHECK: vanilla obligations are seeded with the default pubkey
+    #[account(address = Pubkey::default() @ VaultError::InvalidKaminoAccount)]
+    pub seed1_account: AccountInfo<'info>,
+
+    /// CHECK: vanilla obligations are seeded with the default pubkey
+    #[account(address = Pubkey::default() @ VaultError::InvalidKaminoAccount)]
+    pub seed2_account: AccountInfo<'info>,
+
+    /// CHECK: klend user metadata of `user_state`, checked against its derived address
+    #[account(
+        address = kamino_user_metadata_address(&user_state.key()) @ VaultError::InvalidKaminoAccount
+    )]
+    pub owner_user_metadata: AccountInfo<'info>,
+
+    /// Kamino Lending Program
+    pub klend_program: Program<'info, KaminoLending>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_init_kamino_obligation(
+    ctx: Context<InitKaminoObligation>,
+    obligation_id: u8,
+) -> Result<()> {
+    ensure_not_paused(&ctx.accounts.state, None, PAUSE_PROTOCOLS)?;
+
+    let klend_program = &ctx.accounts.klend_program;
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    msg!(
+        "user_state: {}, lending_market: {}, obligation: {}",
+        ctx.accounts.user_state.key(),
+        ctx.accounts.lending_market.key(),
+        ctx.accounts.obligation.key(),
+    );
+
+    let init_ctx = CpiContext::new_with_signer(
+        klend_program.to_account_info(),
+        crate::klend::cpi::accounts::InitObligation {
+            obligation_owner: ctx.accounts.user_state.to_account_info(),
+            fee_payer: ctx.accounts.signer.to_account_info(),
+            obligation: ctx.accounts.obligation.to_account_info(),
+            lending_market: ctx.accounts.lending_market.to_account_info(),
+            seed1_account: ctx.accounts.seed1_account.to_account_info(),
+            seed2_account: ctx.accounts.seed2_account.to_account_info(),
+            owner_user_metadata: ctx.accounts.owner_user_metadata.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    crate::klend::cpi::init_obligation(
+        init_ctx,
+        InitObligationArgs {
+            tag: KAMINO_VANILLA_OBLIGATION_TAG,
+            id: obligation_id,
+        },
+    )?;
+
+    emit_cpi!(ProtocolAccountInitialized {
+        user: ctx.accounts.user_state.key(),
+        protocol: ctx.accounts.klend_program.key(),
+        account: ctx.accounts.obligation.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use crate::controller::{
+    ensure_not_paused, is_authorized, kamino_lending_market_authority_address,
+    kamino_obligation_farm_address, UserAction, VaultError,
+};
+use crate::events::ProtocolAccountInitialized;
+use crate::get_user_seeds;
+use crate::ids;
+use crate::klend::program::KaminoLending;
+use crate::state::{ProgramState, User, PAUSE_PROTOCOLS};
+use anchor_lang::prelude::*;
+
+/// Kamino farm mode for rewards on deposited collateral.
+pub const KAMINO_FARM_MODE_COLLATERAL: u8 = 0;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitKaminoObligationFarms<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Kamino) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    /// CHECK: owner is checked, klend checks the obligation belongs to `user_state`
+    #[account(mut, owner = crate::klend::ID @ VaultError::InvalidKaminoAccount)]
+    pub obligation: AccountInfo<'info>,
+
+    /// CHECK: authority of `lending_market`, checked against its derived address
+    #[account(
+        mut,
+        address = kamino_lending_market_authority_address(&lending_market.key()) @ VaultError::InvalidKaminoAccount
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    /// CHECK: target program handles
+    #[account(mut)]
+    pub reserve: AccountInfo<'info>,
+
+    /// CHECK: target program handles, klend checks it is the reserve's farm
+    #[account(mut)]
+    pub reserve_farm_state: AccountInfo<'info>,
+
+    /// CHECK: farm user state of `obligation`, checked against its derived address
+    #[account(
+        mut,
+        address = kamino_obligation_farm_address(&reserve_farm_state.key(), &obligation.key()) @ VaultError::InvalidKaminoAccount
+    )]
+    pub obligation_farm: AccountInfo<'info>,
+
+    /// CHECK: target program handles
+    pub lending_market: AccountInfo<'info>,
+
+    /// CHECK: Kamino farms program
+    #[account(address = ids::kamino_farms::ID @ VaultError::InvalidKaminoAccount)]
+    pub farms_program: AccountInfo<'info>,
+
+    /// Kamino Lending Program
+    pub klend_program: Program<'info, KaminoLending>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_init_kamino_obligation_farms(ctx: Context<InitKaminoObligationFarms>) -> Result<()> {
+    ensure_not_paused(&ctx.accounts.state, None, PAUSE_PROTOCOLS)?;
+
+    let klend_program = &ctx.accounts.klend_program;
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    msg!(
+        "user_state: {}, obligation: {}, reserve: {}, obligation_farm: {}",
+        ctx.accounts.user_state.key(),
+        ctx.accounts.obligation.key(),
+        ctx.accounts.reserve.key(),
+        ctx.accounts.obligation_farm.key(),
+    );
+
+    let init_ctx = CpiContext::new_with_signer(
+        klend_program.to_account_info(),
+        crate::klend::cpi::accounts::InitObligationFarmsForReserve {
+            payer: ctx.accounts.signer.to_account_info(),
+            owner: ctx.accounts.user_state.to_account_info(),
+            obligation: ctx.accounts.obligation.to_account_info(),
+            lending_market_authority: ctx.accounts.lending_market_authority.to_account_info(),
+            reserve: ctx.accounts.reserve.to_account_info(),
+            reserve_farm_state: ctx.accounts.reserve_farm_state.to_account_info(),
+            obligation_farm: ctx.accounts.obligation_farm.to_account_info(),
+            lending_market: ctx.accounts.lending_market.to_account_info(),
+            farms_program: ctx.accounts.farms_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    crate::klend::cpi::init_obligation_farms_for_reserve(init_ctx, KAMINO_FARM_MODE_COLLATERAL)?;
+
+    emit_cpi!(ProtocolAccountInitialized {
+        user: ctx.accounts.user_state.key(),
+        protocol: ctx.accounts.klend_program.key(),
+        account: ctx.accounts.obligation_farm.key(),
+    });
+
+    Ok(())
+}
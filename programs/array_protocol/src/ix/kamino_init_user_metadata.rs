@@ -0,0 +1,83 @@
+use crate::controller::{
+    ensure_not_paused, is_authorized, kamino_user_metadata_address, UserAction, VaultError,
+};
+use crate::events::ProtocolAccountInitialized;
+use crate::get_user_seeds;
+use crate::klend::program::KaminoLending;
+use crate::state::{ProgramState, User, PAUSE_PROTOCOLS};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitKaminoUserMetadata<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Kamino) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    /// CHECK: klend user metadata of `user_state`, checked against its derived address
+    #[account(
+        mut,
+        address = kamino_user_metadata_address(&user_state.key()) @ VaultError::InvalidKaminoAccount
+    )]
+    pub user_metadata: AccountInfo<'info>,
+
+    /// Kamino Lending Program
+    pub klend_program: Program<'info, KaminoLending>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_init_kamino_user_metadata(ctx: Context<InitKaminoUserMetadata>) -> Result<()> {
+    ensure_not_paused(&ctx.accounts.state, None, PAUSE_PROTOCOLS)?;
+
+    let klend_program = &ctx.accounts.klend_program;
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    msg!(
+        "user_state: {}, authority: {}, user_metadata: {}",
+        ctx.accounts.user_state.key(),
+        ctx.accounts.user_state.authority.key(),
+        ctx.accounts.user_metadata.key(),
+    );
+
+    let init_ctx = CpiContext::new_with_signer(
+        klend_program.to_account_info(),
+        crate::klend::cpi::accounts::InitUserMetadata {
+            owner: ctx.accounts.user_state.to_account_info(),
+            fee_payer: ctx.accounts.signer.to_account_info(),
+            user_metadata: ctx.accounts.user_metadata.to_account_info(),
+            referrer_user_metadata: None,
+            rent: ctx.accounts.rent.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    crate::klend::cpi::init_user_metadata(init_ctx, Pubkey::default())?;
+
+    emit_cpi!(ProtocolAccountInitialized {
+        user: ctx.accounts.user_state.key(),
+        protocol: ctx.accounts.klend_program.key(),
+        account: ctx.accounts.user_metadata.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,265 @@
+use crate::controller::{book_position_value, ensure_not_paused, find_user_position, VaultError};
+use crate::events::PositionUpdated;
+use crate::klend::accounts::{Obligation, Reserve};
+use crate::klend::program::KaminoLending;
+use crate::klend::types::ObligationCollateral;
+use crate::state::{
+    PositionKind, ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_PROTOCOLS,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+/// Byte offsets into the klend `Obligation` account. It is too large to deserialize on the
+/// stack, so only the owner and the deposit array are read.
+const OBLIGATION_OWNER_OFFSET: usize = 64;
+const OBLIGATION_DEPOSITS_OFFSET: usize = 96;
+const OBLIGATION_COLLATERAL_SIZE: usize = 136;
+const OBLIGATION_DEPOSIT_COUNT: usize = 8;
+
+/// Byte offsets into the klend `Reserve` account, for the fields that make up the collateral
+/// exchange rate.
+const RESERVE_AVAILABLE_AMOUNT_OFFSET: usize = 224;
+const RESERVE_BORROWED_AMOUNT_SF_OFFSET: usize = 232;
+const RESERVE_PROTOCOL_FEES_SF_OFFSET: usize = 344;
+const RESERVE_REFERRER_FEES_SF_OFFSET: usize = 360;
+const RESERVE_PENDING_REFERRER_FEES_SF_OFFSET: usize = 376;
+const RESERVE_COLLATERAL_MINT_SUPPLY_OFFSET: usize = 2592;
+
+/// klend stores fractions as fixed point numbers with 60 fractional bits.
+const FRACTION_BITS: u32 = 60;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct SyncKaminoPosition<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", &vault_index.to_le_bytes()],
+        bump
+    )]
+    pub token_vault: Box<Account<'info, SupportedTokenVault>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Box<Account<'info, UserTokenVault>>,
+
+    /// CHECK: owner and discriminator are checked, the obligation owner is checked in the handler
+    #[account(owner = klend_program.key())]
+    pub obligation: AccountInfo<'info>,
+
+    /// CHECK: owner and discriminator are checked in the handler
+    #[account(owner = klend_program.key())]
+    pub reserve: AccountInfo<'info>,
+
+    /// Kamino Lending Program
+    pub klend_program: Program<'info, KaminoLending>,
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    Ok(u64::try_from_slice(&data[offset..offset + 8])?)
+}
+
+fn read_u128(data: &[u8], offset: usize) -> Result<u128> {
+    Ok(u128::try_from_slice(&data[offset..offset + 16])?)
+}
+
+/// Converts `collateral` of the reserve held in `data` to liquidity at its current exchange rate.
+fn reserve_collateral_value(data: &[u8], collateral: u64) -> Result<u64> {
+    require_gte!(
+        data.len(),
+        RESERVE_COLLATERAL_MINT_SUPPLY_OFFSET + 8,
+        VaultError::InvalidProtocolAccount
+    );
+    require!(
+        data[..8] == *Reserve::DISCRIMINATOR,
+        VaultError::InvalidProtocolAccount
+    );
+
+    let collateral_supply = read_u64(data, RESERVE_COLLATERAL_MINT_SUPPLY_OFFSET)?;
+    if collateral_supply == 0 {
+        return Ok(0);
+    }
+
+    // Total liquidity owned by collateral holders: available + borrowed - unclaimed fees
+    let protocol_fees_sf = read_u128(data, RESERVE_PROTOCOL_FEES_SF_OFFSET)?;
+    let referrer_fees_sf = read_u128(data, RESERVE_REFERRER_FEES_SF_OFFSET)?;
+    let pending_referrer_fees_sf = read_u128(data, RESERVE_PENDING_REFERRER_FEES_SF_OFFSET)?;
+    let fees_sf = protocol_fees_sf
+        .checked_add(referrer_fees_sf)
+        .and_then(|fees| fees.checked_add(pending_referrer_fees_sf))
+        .ok_or(VaultError::Overflow)?;
+    let total_liquidity_sf = ((read_u64(data, RESERVE_AVAILABLE_AMOUNT_OFFSET)? as u128)
+        << FRACTION_BITS)
+        .checked_add(read_u128(data, RESERVE_BORROWED_AMOUNT_SF_OFFSET)?)
+        .ok_or(VaultError::Overflow)?
+        .saturating_sub(fees_sf);
+
+    // Liquidity per unit of collateral, rounded down so the value is never overstated
+    let rate_sf = total_liquidity_sf / collateral_supply as u128;
+    let value = (collateral as u128)
+        .checked_mul(rate_sf)
+        .ok_or(VaultError::Overflow)?
+        >> FRACTION_BITS;
+    Ok(u64::try_from(value).map_err(|_| VaultError::Overflow)?)
+}
+
+/// Reads the user's collateral in the reserve from the obligation, converts it to liquidity at
+/// the reserve's current exchange rate and stores it on the position. The change in value since
+/// the last sync is booked into the user's share price. Anyone may call this, it only refreshes
+/// bookkeeping.
+pub fn handle_sync_kamino_position(
+    ctx: Context<SyncKaminoPosition>,
+    vault_index: u16,
+) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_PROTOCOLS,
+    )?;
+
+    let reserve_key = ctx.accounts.reserve.key();
+
+    let collateral = {
+        let data = ctx.accounts.obligation.data.borrow();
+        require_gte!(
+            data.len(),
+            OBLIGATION_DEPOSITS_OFFSET + OBLIGATION_DEPOSIT_COUNT * OBLIGATION_COLLATERAL_SIZE,
+            VaultError::InvalidProtocolAccount
+        );
+        require!(
+            data[..8] == *Obligation::DISCRIMINATOR,
+            VaultError::InvalidProtocolAccount
+        );
+
+        let owner =
+            Pubkey::try_from_slice(&data[OBLIGATION_OWNER_OFFSET..OBLIGATION_OWNER_OFFSET + 32])?;
+        require_keys_eq!(
+            owner,
+            ctx.accounts.user_state.key(),
+            VaultError::InvalidProtocolAccount
+        );
+
+        let mut collateral = 0u64;
+        for i in 0..OBLIGATION_DEPOSIT_COUNT {
+            let start = OBLIGATION_DEPOSITS_OFFSET + i * OBLIGATION_COLLATERAL_SIZE;
+            let deposit = ObligationCollateral::deserialize(
+                &mut &data[start..start + OBLIGATION_COLLATERAL_SIZE],
+            )?;
+            if deposit.deposit_reserve == reserve_key {
+                collateral = deposit.deposited_amount;
+                break;
+            }
+        }
+        collateral
+    };
+
+    let value = reserve_collateral_value(&ctx.accounts.reserve.data.borrow(), collateral)?;
+
+    let user_state = &mut ctx.accounts.user_state;
+    let idx = find_user_position(
+        user_state,
+        vault_index,
+        ctx.accounts.klend_program.key(),
+        reserve_key,
+        0,
+        PositionKind::Lending,
+    )?;
+    let slot = Clock::get()?.slot;
+
+    let pos = &mut user_state.positions[idx];
+    let old_value = pos.value;
+    pos.value = value;
+    pos.last_synced_slot = slot;
+    let deposited_amount = pos.deposited_amount;
+
+    book_position_value(
+        &mut ctx.accounts.token_vault,
+        &mut ctx.accounts.user_token_vault,
+        old_value,
+        value,
+    )?;
+
+    msg!(
+        "Synced Kamino position {}: collateral {} value {} (deposited {})",
+        idx,
+        collateral,
+        value,
+        deposited_amount
+    );
+    emit_cpi!(PositionUpdated {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol: ctx.accounts.klend_program.key(),
+        protocol_vault: reserve_key,
+        value,
+        deposited_amount,
+        user_balance: ctx.accounts.user_token_vault.balance,
+        slot,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reserve_data(
+        available: u64,
+        borrowed: u64,
+        fees: [u64; 3],
+        collateral_supply: u64,
+        market_price: u64,
+    ) -> Vec<u8> {
+        let mut data = vec![0u8; RESERVE_COLLATERAL_MINT_SUPPLY_OFFSET + 8];
+        data[..8].copy_from_slice(Reserve::DISCRIMINATOR);
+        data[224..232].copy_from_slice(&available.to_le_bytes());
+        data[232..248].copy_from_slice(&((borrowed as u128) << FRACTION_BITS).to_le_bytes());
+        // market_price_sf follows borrowed_amount_sf and must not be read as part of it
+        data[248..264].copy_from_slice(&((market_price as u128) << FRACTION_BITS).to_le_bytes());
+        for (fee, offset) in fees.iter().zip([344, 360, 376]) {
+            data[offset..offset + 16]
+                .copy_from_slice(&((*fee as u128) << FRACTION_BITS).to_le_bytes());
+        }
+        data[2592..2600].copy_from_slice(&collateral_supply.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn values_collateral_at_the_reserve_exchange_rate() {
+        // 1_000 available + 1_200 borrowed - 200 fees = 2_000 liquidity for 1_000 collateral
+        let data = reserve_data(1_000, 1_200, [100, 50, 50], 1_000, 7);
+        assert_eq!(reserve_collateral_value(&data, 100).unwrap(), 200);
+        assert_eq!(reserve_collateral_value(&data, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn empty_reserves_are_worth_nothing() {
+        let data = reserve_data(1_000, 0, [0; 3], 0, 7);
+        assert_eq!(reserve_collateral_value(&data, 100).unwrap(), 0);
+    }
+
+    #[test]
+    fn rejects_accounts_that_are_not_reserves() {
+        let mut data = reserve_data(1_000, 0, [0; 3], 1_000, 7);
+        data[0] ^= 1;
+        assert!(reserve_collateral_value(&data, 100).is_err());
+        assert!(reserve_collateral_value(&data[..100], 100).is_err());
+    }
+}
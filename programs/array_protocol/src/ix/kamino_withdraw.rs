@@ -0,0 +1,225 @@
+use crate::controller::{
+    book_withdrawn_interest, ensure_not_paused, find_user_position, is_authorized,
+    kamino_lending_market_authority_address, kamino_obligation_address, update_user_position,
+    UserAction, VaultError,
+};
+use crate::events::ProtocolWithdrawn;
+use crate::get_user_seeds;
+use crate::klend::program::KaminoLending;
+use crate::state::{
+    PositionKind, ProgramState, SupportedTokenVault, User, UserTokenVault, PAUSE_PROTOCOLS,
+    PAUSE_WITHDRAWALS,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::{instructions::Instructions, SysvarId};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16, obligation_id: u8)]
+pub struct KaminoWithdraw<'info> {
+    pub signer: Signer<'info>,
+
+    /// reserve_liquidity_mint
+    #[account(mut, owner = token_program.key())]
+    pub token_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Kamino) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Box<Account<'info, User>>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", &vault_index.to_le_bytes()],
+        bump
+    )]
+    pub token_vault: Box<Account<'info, SupportedTokenVault>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        token::mint = token_vault_mint,
+        token::authority = user_state,
+        token::token_program = token_program
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>, // user_destination_liquidity
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Account<'info, UserTokenVault>,
+
+    /// CHECK: vanilla obligation `obligation_id` of `user_state` in `lending_market`, checked
+    /// against its derived address
+    #[account(
+        mut,
+        address = kamino_obligation_address(&user_state.key(), &lending_market.key(), obligation_id) @ VaultError::InvalidKaminoAccount
+    )]
+    pub obligation: AccountInfo<'info>,
+
+    /// CHECK: klend checks it is the obligation's market
+    pub lending_market: AccountInfo<'info>,
+
+    /// CHECK: authority of `lending_market`, checked against its derived address
+    #[account(
+        address = kamino_lending_market_authority_address(&lending_market.key()) @ VaultError::InvalidKaminoAccount
+    )]
+    pub lending_market_authority: AccountInfo<'info>,
+
+    /// CHECK: target program handles
+    #[account(mut)]
+    pub reserve: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub reserve_source_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub reserve_collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub reserve_liquidity_supply: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: target program handles
+    #[account(address = Instructions::id())]
+    pub instruction_sysvar_account: AccountInfo<'info>,
+
+    /// Kamino Lending Program
+    pub klend_program: Program<'info, KaminoLending>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program of the reserve collateral mint, klend always creates it with SPL Token
+    pub collateral_token_program: Program<'info, Token>,
+}
+
+/// Redeems `collateral_amount` reserve collateral from the user's obligation back into the
+/// user's vault token account. Liquidity above the position's principal is interest and raises
+/// the user's share price.
+pub fn handle_kamino_withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, KaminoWithdraw<'info>>,
+    vault_index: u16,
+    _obligation_id: u8,
+    collateral_amount: u64,
+) -> Result<()> {
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        PAUSE_WITHDRAWALS | PAUSE_PROTOCOLS,
+    )?;
+
+    let kamino_program = ctx.accounts.klend_program.to_account_info();
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    let balance_before = ctx.accounts.user_vault_token_account.amount;
+
+    let cpi_accounts =
+        crate::klend::cpi::accounts::WithdrawObligationCollateralAndRedeemReserveCollateral {
+            owner: ctx.accounts.user_state.to_account_info(),
+            obligation: ctx.accounts.obligation.to_account_info(),
+            lending_market: ctx.accounts.lending_market.to_account_info(),
+            lending_market_authority: ctx.accounts.lending_market_authority.to_account_info(),
+            withdraw_reserve: ctx.accounts.reserve.to_account_info(),
+            reserve_liquidity_mint: ctx.accounts.token_vault_mint.to_account_info(),
+            reserve_source_collateral: ctx.accounts.reserve_source_collateral.to_account_info(),
+            reserve_collateral_mint: ctx.accounts.reserve_collateral_mint.to_account_info(),
+            reserve_liquidity_supply: ctx.accounts.reserve_liquidity_supply.to_account_info(),
+            user_destination_liquidity: ctx.accounts.user_vault_token_account.to_account_info(),
+            placeholder_user_destination_collateral: None,
+            collateral_token_program: ctx.accounts.collateral_token_program.to_account_info(),
+            liquidity_token_program: ctx.accounts.token_program.to_account_info(),
+            instruction_sysvar_account: ctx.accounts.instruction_sysvar_account.to_account_info(),
+        };
+
+    let cpi_ctx = CpiContext::new_with_signer(kamino_program, cpi_accounts, signer_seeds);
+
+    crate::klend::cpi::withdraw_obligation_collateral_and_redeem_reserve_collateral(
+        cpi_ctx,
+        collateral_amount,
+    )?;
+
+    // Kamino redeems collateral at the reserve's exchange rate, so measure what actually arrived.
+    ctx.accounts.user_vault_token_account.reload()?;
+    let received = ctx
+        .accounts
+        .user_vault_token_account
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(VaultError::Underflow)?;
+
+    // Interest earned on top of the principal is not part of the position, it is booked into the
+    // user's share price instead.
+    let protocol = ctx.accounts.klend_program.key();
+    let reserve_key = ctx.accounts.reserve.key();
+    let deposited = find_user_position(
+        &ctx.accounts.user_state,
+        vault_index,
+        protocol,
+        reserve_key,
+        0,
+        PositionKind::Lending,
+    )
+    .map(|idx| ctx.accounts.user_state.positions[idx].deposited_amount)
+    .unwrap_or(0);
+    let principal = received.min(deposited);
+
+    // Update the users position data.
+    let post_balance = update_user_position(
+        &mut ctx.accounts.user_state,
+        ctx.accounts.user_token_vault.key(),
+        ctx.accounts.user_vault_token_account.key(),
+        protocol,
+        reserve_key,
+        vault_index,
+        0,
+        0,
+        PositionKind::Lending,
+        principal,
+        true,
+    )?;
+    let idx = find_user_position(
+        &ctx.accounts.user_state,
+        vault_index,
+        protocol,
+        reserve_key,
+        0,
+        PositionKind::Lending,
+    )?;
+    book_withdrawn_interest(
+        &mut ctx.accounts.user_state,
+        idx,
+        &mut ctx.accounts.token_vault,
+        &mut ctx.accounts.user_token_vault,
+        received - principal,
+    )?;
+
+    emit_cpi!(ProtocolWithdrawn {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        protocol,
+        protocol_vault: reserve_key,
+        amount: received,
+        post_balance,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::controller::VaultError;
+use crate::events::UserResized;
+use crate::state::{Position, User, LEGACY_POSITION_SIZE};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+
+/// Accounts for `migrate_user`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateUser<'info> {
+    /// Pays the rent for the larger positions and the length prefix.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: owner is checked, layout and discriminator are checked in the handler. It can't
+    /// be an `Account<User>` until it has been migrated.
+    #[account(mut, owner = crate::ID)]
+    pub user_state: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for `migrate_user`.
+/// Rewrites a `User` created with a fixed array of 8 positions in the `LEGACY_POSITION_SIZE`
+/// layout into the `Vec` layout, converting each position field by field. The header is kept as
+/// it is. Anyone may call this.
+pub fn handle_migrate_user(ctx: Context<MigrateUser>) -> Result<()> {
+    let user_info = ctx.accounts.user_state.to_account_info();
+    // `as_chunks` is newer than the rustc shipped with the Solana platform tools
+    #[allow(clippy::chunks_exact_to_as_chunks)]
+    let positions = {
+        let data = user_info.try_borrow_data()?;
+        require_eq!(data.len(), User::LEGACY_SIZE, VaultError::NotLegacyUser);
+        require!(data[..8] == *User::DISCRIMINATOR, VaultError::NotLegacyUser);
+
+        data[User::POSITIONS_OFFSET..]
+            .chunks_exact(LEGACY_POSITION_SIZE)
+            .map(Position::from_legacy)
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let new_size = User::space(positions.len());
+    let required = Rent::get()?
+        .minimum_balance(new_size)
+        .saturating_sub(user_info.lamports());
+    if required > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.signer.to_account_info(),
+                    to: user_info.clone(),
+                },
+            ),
+            required,
+        )?;
+    }
+
+    user_info.realloc(new_size, false)?;
+    {
+        let mut data = user_info.try_borrow_mut_data()?;
+        positions.serialize(&mut &mut data[User::POSITIONS_OFFSET..])?;
+    }
+
+    msg!(
+        "Migrated user {} to {} position slots",
+        user_info.key(),
+        positions.len()
+    );
+
+    emit_cpi!(UserResized {
+        user: user_info.key(),
+        position_slots: positions.len() as u32,
+    });
+
+    Ok(())
+}
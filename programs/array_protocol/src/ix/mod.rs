@@ -1,23 +1,97 @@
+pub mod accept_admin;
+pub mod add_registry_market;
+pub mod add_user_positions;
+pub mod cancel_withdraw;
+pub mod close_user;
+pub mod close_user_token_vault;
+pub mod collect_fees;
+pub mod deposit_sol;
 pub mod deposit_spl;
+pub mod drift_add_insurance_fund_stake;
+pub mod drift_delete_user;
 pub mod drift_deposit;
+pub mod drift_init_insurance_fund_stake;
 pub mod drift_init_user;
 pub mod drift_init_user_stats;
+pub mod drift_remove_insurance_fund_stake;
+pub mod drift_request_remove_insurance_fund_stake;
+pub mod drift_sync_position;
+pub mod drift_transfer_deposit;
+pub mod drift_update_delegate;
 pub mod drift_withdraw;
+pub mod execute_withdraw;
 pub mod init_program_state;
+pub mod init_protocol_registry;
 pub mod init_token_vault;
 pub mod init_user;
 pub mod init_user_token_vault;
 pub mod kamino_deposit;
+pub mod kamino_init_obligation;
+pub mod kamino_init_obligation_farms;
+pub mod kamino_init_user_metadata;
+pub mod kamino_sync_position;
+pub mod kamino_withdraw;
+pub mod migrate_user;
+pub mod propose_admin;
+pub mod rebalance;
+pub mod request_withdraw;
+pub mod resize_token_vault;
+pub mod resize_user_token_vault;
+pub mod set_delegate;
+pub mod set_deposit_caps;
+pub mod set_pause;
+pub mod set_treasury;
+pub mod set_vault_fees;
+pub mod set_vault_pause;
+pub mod set_withdrawal_delay;
+pub mod withdraw_sol;
 pub mod withdraw_spl;
 
+pub use accept_admin::*;
+pub use add_registry_market::*;
+pub use add_user_positions::*;
+pub use cancel_withdraw::*;
+pub use close_user::*;
+pub use close_user_token_vault::*;
+pub use collect_fees::*;
+pub use deposit_sol::*;
 pub use deposit_spl::*;
+pub use drift_add_insurance_fund_stake::*;
+pub use drift_delete_user::*;
 pub use drift_deposit::*;
+pub use drift_init_insurance_fund_stake::*;
 pub use drift_init_user::*;
 pub use drift_init_user_stats::*;
+pub use drift_remove_insurance_fund_stake::*;
+pub use drift_request_remove_insurance_fund_stake::*;
+pub use drift_sync_position::*;
+pub use drift_transfer_deposit::*;
+pub use drift_update_delegate::*;
 pub use drift_withdraw::*;
+pub use execute_withdraw::*;
 pub use init_program_state::*;
+pub use init_protocol_registry::*;
 pub use init_token_vault::*;
 pub use init_user::*;
 pub use init_user_token_vault::*;
 pub use kamino_deposit::*;
+pub use kamino_init_obligation::*;
+pub use kamino_init_obligation_farms::*;
+pub use kamino_init_user_metadata::*;
+pub use kamino_sync_position::*;
+pub use kamino_withdraw::*;
+pub use migrate_user::*;
+pub use propose_admin::*;
+pub use rebalance::*;
+pub use request_withdraw::*;
+pub use resize_token_vault::*;
+pub use resize_user_token_vault::*;
+pub use set_delegate::*;
+pub use set_deposit_caps::*;
+pub use set_pause::*;
+pub use set_treasury::*;
+pub use set_vault_fees::*;
+pub use set_vault_pause::*;
+pub use set_withdrawal_delay::*;
+pub use withdraw_sol::*;
 pub use withdraw_spl::*;
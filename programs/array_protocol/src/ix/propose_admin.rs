@@ -0,0 +1,34 @@
+use crate::controller::VaultError;
+use crate::events::AdminUpdated;
+use crate::state::ProgramState;
+use anchor_lang::prelude::*;
+
+/// Accounts for `propose_admin`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin
+    )]
+    pub state: Account<'info, ProgramState>,
+}
+
+/// Handler for `propose_admin`.
+/// First step of an admin transfer. Proposing `Pubkey::default()` cancels a pending transfer.
+pub fn handle_propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    ctx.accounts.state.pending_admin = new_admin;
+
+    msg!("Admin transfer proposed to {}", new_admin);
+
+    emit_cpi!(AdminUpdated {
+        admin: ctx.accounts.state.admin,
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,570 @@
+use crate::controller::{
+    book_withdrawn_interest, ensure_drift_accounts, ensure_not_paused, ensure_within_position,
+    find_user_position, is_authorized, transfer_fee, update_user_position, UserAction, VaultError,
+};
+use crate::events::{ProtocolDeposited, ProtocolWithdrawn};
+use crate::klend::program::KaminoLending;
+use crate::state::{
+    PositionKind, ProgramState, ProtocolRegistry, RegisteredMarket, SupportedTokenVault, User,
+    UserTokenVault, PAUSE_DEPOSITS, PAUSE_PROTOCOLS, PAUSE_WITHDRAWALS,
+};
+use crate::{drift, get_user_seeds};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::{instructions::Instructions, SysvarId};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use drift::program::Drift;
+
+/// Where funds are moved from or to by `rebalance`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RebalanceTarget {
+    /// The user's vault token account (`user_vault_token_account`).
+    Idle,
+    /// The Drift spot market `market_index` in sub-account `sub_account_id`.
+    Drift,
+    /// The Kamino `reserve`.
+    Kamino,
+}
+
+impl RebalanceTarget {
+    fn action(&self) -> Option<UserAction> {
+        match self {
+            RebalanceTarget::Idle => None,
+            RebalanceTarget::Drift => Some(UserAction::Drift),
+            RebalanceTarget::Kamino => Some(UserAction::Kamino),
+        }
+    }
+}
+
+/// Accounts for `rebalance`.
+/// The Drift accounts are only required when moving from or to Drift, and the Kamino accounts
+/// only when moving from or to Kamino. Drift's remaining accounts (oracles, spot markets) are
+/// passed as remaining accounts.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct Rebalance<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// reserve_liquidity_mint for Kamino
+    #[account(mut, owner = token_program.key())]
+    pub token_vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+    )]
+    pub user_state: Box<Account<'info, User>>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", &vault_index.to_le_bytes()],
+        bump
+    )]
+    pub token_vault: Box<Account<'info, SupportedTokenVault>>,
+
+    #[account(
+        seeds = [b"protocol_registry".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub registry: Box<Account<'info, ProtocolRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        token::mint = token_vault_mint,
+        token::authority = user_state,
+        token::token_program = token_program
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Box<Account<'info, UserTokenVault>>,
+
+    /// CHECK: verified by `ensure_drift_accounts`
+    #[account(mut)]
+    pub drift_state: Option<AccountInfo<'info>>,
+
+    /// CHECK: verified by `ensure_drift_accounts`
+    #[account(mut)]
+    pub drift_user: Option<AccountInfo<'info>>,
+
+    /// CHECK: verified by `ensure_drift_accounts`
+    #[account(mut)]
+    pub drift_user_stats: Option<AccountInfo<'info>>,
+
+    #[account(mut)]
+    pub spot_market_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: verified by `ensure_drift_accounts`
+    pub drift_signer: Option<AccountInfo<'info>>,
+
+    /// Drift Program
+    pub drift_program: Option<Program<'info, Drift>>,
+
+    /// CHECK: target program handles
+    #[account(mut)]
+    pub obligation: Option<AccountInfo<'info>>,
+
+    /// CHECK: target program handles
+    pub lending_market: Option<AccountInfo<'info>>,
+
+    /// CHECK: target program handles
+    pub lending_market_authority: Option<AccountInfo<'info>>,
+
+    /// CHECK: target program handles
+    #[account(mut)]
+    pub reserve: Option<AccountInfo<'info>>,
+
+    #[account(mut)]
+    pub reserve_liquidity_supply: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub reserve_collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// The reserve's collateral supply, deposited into and withdrawn from
+    #[account(mut)]
+    pub reserve_collateral_supply: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: target program handles
+    #[account(address = Instructions::id())]
+    pub instruction_sysvar_account: Option<AccountInfo<'info>>,
+
+    /// Kamino Lending Program
+    pub klend_program: Option<Program<'info, KaminoLending>>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program of the reserve collateral mint, klend always creates it with SPL Token
+    pub collateral_token_program: Program<'info, Token>,
+}
+
+/// Drift accounts unwrapped from `Rebalance`.
+struct DriftAccounts<'info> {
+    state: AccountInfo<'info>,
+    user: AccountInfo<'info>,
+    user_stats: AccountInfo<'info>,
+    spot_market_vault: AccountInfo<'info>,
+    drift_signer: Option<AccountInfo<'info>>,
+    program: AccountInfo<'info>,
+}
+
+/// Kamino accounts unwrapped from `Rebalance`.
+struct KaminoAccounts<'info> {
+    obligation: AccountInfo<'info>,
+    lending_market: AccountInfo<'info>,
+    lending_market_authority: AccountInfo<'info>,
+    reserve: AccountInfo<'info>,
+    reserve_liquidity_supply: AccountInfo<'info>,
+    reserve_collateral_mint: AccountInfo<'info>,
+    reserve_collateral_supply: AccountInfo<'info>,
+    instruction_sysvar_account: AccountInfo<'info>,
+    program: AccountInfo<'info>,
+}
+
+impl<'info> Rebalance<'info> {
+    fn drift_accounts(&self) -> Result<DriftAccounts<'info>> {
+        match (
+            &self.drift_state,
+            &self.drift_user,
+            &self.drift_user_stats,
+            &self.spot_market_vault,
+            &self.drift_program,
+        ) {
+            (Some(state), Some(user), Some(user_stats), Some(spot_market_vault), Some(program)) => {
+                Ok(DriftAccounts {
+                    state: state.to_account_info(),
+                    user: user.to_account_info(),
+                    user_stats: user_stats.to_account_info(),
+                    spot_market_vault: spot_market_vault.to_account_info(),
+                    drift_signer: self.drift_signer.as_ref().map(|s| s.to_account_info()),
+                    program: program.to_account_info(),
+                })
+            }
+            _ => err!(VaultError::MissingProtocolAccounts),
+        }
+    }
+
+    fn kamino_accounts(&self) -> Result<KaminoAccounts<'info>> {
+        match (
+            &self.obligation,
+            &self.lending_market,
+            &self.lending_market_authority,
+            &self.reserve,
+            &self.reserve_liquidity_supply,
+            &self.reserve_collateral_mint,
+            &self.reserve_collateral_supply,
+            &self.instruction_sysvar_account,
+            &self.klend_program,
+        ) {
+            (
+                Some(obligation),
+                Some(lending_market),
+                Some(lending_market_authority),
+                Some(reserve),
+                Some(reserve_liquidity_supply),
+                Some(reserve_collateral_mint),
+                Some(reserve_collateral_supply),
+                Some(instruction_sysvar_account),
+                Some(program),
+            ) => Ok(KaminoAccounts {
+                obligation: obligation.to_account_info(),
+                lending_market: lending_market.to_account_info(),
+                lending_market_authority: lending_market_authority.to_account_info(),
+                reserve: reserve.to_account_info(),
+                reserve_liquidity_supply: reserve_liquidity_supply.to_account_info(),
+                reserve_collateral_mint: reserve_collateral_mint.to_account_info(),
+                reserve_collateral_supply: reserve_collateral_supply.to_account_info(),
+                instruction_sysvar_account: instruction_sysvar_account.to_account_info(),
+                program: program.to_account_info(),
+            }),
+            _ => err!(VaultError::MissingProtocolAccounts),
+        }
+    }
+
+    /// The (protocol, protocol_vault, sub_account_id, market_index) identifying the position for
+    /// `target`. Only Drift positions carry a sub-account and market index.
+    fn position_key(
+        &self,
+        target: RebalanceTarget,
+        sub_account_id: u16,
+        market_index: u16,
+    ) -> Result<(Pubkey, Pubkey, u16, u16)> {
+        match target {
+            RebalanceTarget::Idle => err!(VaultError::InvalidRebalance),
+            RebalanceTarget::Drift => {
+                let drift = self.drift_accounts()?;
+                Ok((
+                    drift.program.key(),
+                    drift.spot_market_vault.key(),
+                    sub_account_id,
+                    market_index,
+                ))
+            }
+            RebalanceTarget::Kamino => {
+                let kamino = self.kamino_accounts()?;
+                Ok((kamino.program.key(), kamino.reserve.key(), 0, 0))
+            }
+        }
+    }
+}
+
+/// Moves funds from one protocol to another (or to/from the idle vault) in a single
+/// instruction. For Drift `amount` is a token amount, for Kamino it is a collateral amount. The
+/// tokens that actually arrive in `user_vault_token_account` are what gets deposited into `to`.
+pub fn handle_rebalance<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Rebalance<'info>>,
+    vault_index: u16,
+    from: RebalanceTarget,
+    to: RebalanceTarget,
+    sub_account_id: u16,
+    market_index: u16,
+    amount: u64,
+) -> Result<()> {
+    require!(from != to, VaultError::InvalidRebalance);
+
+    let mut pause_flags = PAUSE_PROTOCOLS;
+    if from != RebalanceTarget::Idle {
+        pause_flags |= PAUSE_WITHDRAWALS;
+    }
+    if to != RebalanceTarget::Idle {
+        pause_flags |= PAUSE_DEPOSITS;
+    }
+    ensure_not_paused(
+        &ctx.accounts.state,
+        Some(&ctx.accounts.token_vault),
+        pause_flags,
+    )?;
+    let signer = ctx.accounts.signer.key();
+    for action in [from.action(), to.action()].into_iter().flatten() {
+        require!(
+            is_authorized(&ctx.accounts.user_state, &signer, action),
+            VaultError::UnauthorizedUser
+        );
+    }
+
+    if from == RebalanceTarget::Drift || to == RebalanceTarget::Drift {
+        let drift = ctx.accounts.drift_accounts()?;
+        ensure_drift_accounts(
+            &ctx.accounts.user_state.key(),
+            sub_account_id,
+            market_index,
+            drift.state.key,
+            drift.user.key,
+            drift.user_stats.key,
+            drift.spot_market_vault.key,
+            drift.drift_signer.as_ref().map(|s| s.key),
+        )?;
+    }
+
+    let user_state_info = ctx.accounts.user_state.to_account_info();
+    let user_vault_info = ctx.accounts.user_vault_token_account.to_account_info();
+    let token_program_info = ctx.accounts.token_program.to_account_info();
+    let mint_info = ctx.accounts.token_vault_mint.to_account_info();
+
+    let authority = ctx.accounts.user_state.authority;
+    let bump = ctx.accounts.user_state.bump;
+    let seeds = get_user_seeds(&authority, &bump);
+    let signer_seeds = &[&seeds[..]];
+
+    let balance_before = ctx.accounts.user_vault_token_account.amount;
+
+    // 1) Pull funds out of the source protocol
+    match from {
+        RebalanceTarget::Idle => {}
+        RebalanceTarget::Drift => {
+            // Withdrawals are capped at what the position holds, so nothing borrowed from Drift
+            // is booked as interest.
+            let (protocol, protocol_vault, _, _) =
+                ctx.accounts
+                    .position_key(from, sub_account_id, market_index)?;
+            let idx = find_user_position(
+                &ctx.accounts.user_state,
+                vault_index,
+                protocol,
+                protocol_vault,
+                sub_account_id,
+                PositionKind::Lending,
+            )?;
+            ensure_within_position(&ctx.accounts.user_state, idx, amount)?;
+
+            let drift = ctx.accounts.drift_accounts()?;
+            let cpi_accounts = drift::cpi::accounts::Withdraw {
+                state: drift.state,
+                user: drift.user,
+                user_stats: drift.user_stats,
+                authority: user_state_info.clone(),
+                spot_market_vault: drift.spot_market_vault,
+                drift_signer: drift
+                    .drift_signer
+                    .ok_or(VaultError::MissingProtocolAccounts)?,
+                user_token_account: user_vault_info.clone(),
+                token_program: token_program_info.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(drift.program, cpi_accounts, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            drift::cpi::withdraw(cpi_ctx, market_index, amount, true)?;
+        }
+        RebalanceTarget::Kamino => {
+            let kamino = ctx.accounts.kamino_accounts()?;
+            let cpi_accounts =
+                crate::klend::cpi::accounts::WithdrawObligationCollateralAndRedeemReserveCollateral {
+                    owner: user_state_info.clone(),
+                    obligation: kamino.obligation,
+                    lending_market: kamino.lending_market,
+                    lending_market_authority: kamino.lending_market_authority,
+                    withdraw_reserve: kamino.reserve,
+                    reserve_liquidity_mint: mint_info.clone(),
+                    reserve_source_collateral: kamino.reserve_collateral_supply,
+                    reserve_collateral_mint: kamino.reserve_collateral_mint,
+                    reserve_liquidity_supply: kamino.reserve_liquidity_supply,
+                    user_destination_liquidity: user_vault_info.clone(),
+                    placeholder_user_destination_collateral: None,
+                    collateral_token_program: ctx.accounts.collateral_token_program.to_account_info(),
+                    liquidity_token_program: token_program_info.clone(),
+                    instruction_sysvar_account: kamino.instruction_sysvar_account,
+                };
+            let cpi_ctx = CpiContext::new_with_signer(kamino.program, cpi_accounts, signer_seeds);
+            crate::klend::cpi::withdraw_obligation_collateral_and_redeem_reserve_collateral(
+                cpi_ctx, amount,
+            )?;
+        }
+    }
+
+    // 2) Measure what actually arrived in the user's vault
+    let moved = if from == RebalanceTarget::Idle {
+        amount
+    } else {
+        ctx.accounts.user_vault_token_account.reload()?;
+        ctx.accounts
+            .user_vault_token_account
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(VaultError::Underflow)?
+    };
+    msg!("Rebalancing {} tokens from {:?} to {:?}", moved, from, to);
+
+    // 3) Push the funds into the destination protocol
+    match to {
+        RebalanceTarget::Idle => {}
+        RebalanceTarget::Drift => {
+            let drift = ctx.accounts.drift_accounts()?;
+            require!(
+                ctx.accounts
+                    .registry
+                    .is_registered(&RegisteredMarket::drift(
+                        drift.program.key(),
+                        drift.spot_market_vault.key(),
+                        market_index,
+                    )),
+                VaultError::MarketNotRegistered
+            );
+            let cpi_accounts = drift::cpi::accounts::Deposit {
+                state: drift.state,
+                user: drift.user,
+                user_stats: drift.user_stats,
+                authority: user_state_info.clone(),
+                spot_market_vault: drift.spot_market_vault,
+                user_token_account: user_vault_info.clone(),
+                token_program: token_program_info.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(drift.program, cpi_accounts, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            drift::cpi::deposit(cpi_ctx, market_index, moved, false)?;
+        }
+        RebalanceTarget::Kamino => {
+            let kamino = ctx.accounts.kamino_accounts()?;
+            require!(
+                ctx.accounts
+                    .registry
+                    .is_registered(&RegisteredMarket::kamino(
+                        kamino.program.key(),
+                        kamino.reserve.key(),
+                        kamino.lending_market.key(),
+                    )),
+                VaultError::MarketNotRegistered
+            );
+            let cpi_accounts =
+                crate::klend::cpi::accounts::DepositReserveLiquidityAndObligationCollateral {
+                    owner: user_state_info.clone(),
+                    obligation: kamino.obligation,
+                    lending_market: kamino.lending_market,
+                    lending_market_authority: kamino.lending_market_authority,
+                    reserve: kamino.reserve,
+                    reserve_liquidity_mint: mint_info.clone(),
+                    reserve_liquidity_supply: kamino.reserve_liquidity_supply,
+                    reserve_collateral_mint: kamino.reserve_collateral_mint,
+                    reserve_destination_deposit_collateral: kamino.reserve_collateral_supply,
+                    user_source_liquidity: user_vault_info.clone(),
+                    placeholder_user_destination_collateral: None,
+                    collateral_token_program: ctx
+                        .accounts
+                        .collateral_token_program
+                        .to_account_info(),
+                    liquidity_token_program: token_program_info.clone(),
+                    instruction_sysvar_account: kamino.instruction_sysvar_account,
+                };
+            let cpi_ctx = CpiContext::new_with_signer(kamino.program, cpi_accounts, signer_seeds);
+            crate::klend::cpi::deposit_reserve_liquidity_and_obligation_collateral(cpi_ctx, moved)?;
+        }
+    }
+
+    // 4) Update both positions with the measured amount
+    let user_token_vault_key = ctx.accounts.user_token_vault.key();
+    let user_vault_token_account_key = ctx.accounts.user_vault_token_account.key();
+
+    if from != RebalanceTarget::Idle {
+        let (protocol, protocol_vault, sub_account_id, market_index) =
+            ctx.accounts
+                .position_key(from, sub_account_id, market_index)?;
+
+        // Interest earned on top of the principal is not part of the position, it is booked into
+        // the user's share price instead.
+        let deposited = ctx
+            .accounts
+            .user_state
+            .positions
+            .iter()
+            .find(|pos| {
+                pos.vault_index == vault_index
+                    && pos.protocol == protocol
+                    && pos.protocol_vault == protocol_vault
+                    && pos.sub_account_id == sub_account_id
+                    && pos.kind == PositionKind::Lending
+            })
+            .map(|pos| pos.deposited_amount)
+            .unwrap_or(0);
+
+        let post_balance = update_user_position(
+            &mut ctx.accounts.user_state,
+            user_token_vault_key,
+            user_vault_token_account_key,
+            protocol,
+            protocol_vault,
+            vault_index,
+            sub_account_id,
+            market_index,
+            PositionKind::Lending,
+            moved.min(deposited),
+            true,
+        )?;
+        let idx = find_user_position(
+            &ctx.accounts.user_state,
+            vault_index,
+            protocol,
+            protocol_vault,
+            sub_account_id,
+            PositionKind::Lending,
+        )?;
+        book_withdrawn_interest(
+            &mut ctx.accounts.user_state,
+            idx,
+            &mut ctx.accounts.token_vault,
+            &mut ctx.accounts.user_token_vault,
+            moved - moved.min(deposited),
+        )?;
+
+        emit_cpi!(ProtocolWithdrawn {
+            user: ctx.accounts.user_state.key(),
+            vault_index,
+            protocol,
+            protocol_vault,
+            amount: moved,
+            post_balance,
+        });
+    }
+
+    if to != RebalanceTarget::Idle {
+        let (protocol, protocol_vault, sub_account_id, market_index) =
+            ctx.accounts
+                .position_key(to, sub_account_id, market_index)?;
+
+        // Token-2022 transfer fees are withheld from what reaches the protocol
+        let received = moved
+            .checked_sub(transfer_fee(
+                &ctx.accounts.token_vault_mint.to_account_info(),
+                moved,
+            )?)
+            .ok_or(VaultError::Underflow)?;
+
+        let post_balance = update_user_position(
+            &mut ctx.accounts.user_state,
+            user_token_vault_key,
+            user_vault_token_account_key,
+            protocol,
+            protocol_vault,
+            vault_index,
+            sub_account_id,
+            market_index,
+            PositionKind::Lending,
+            received,
+            false,
+        )?;
+
+        emit_cpi!(ProtocolDeposited {
+            user: ctx.accounts.user_state.key(),
+            vault_index,
+            protocol,
+            protocol_vault,
+            amount: received,
+            post_balance,
+        });
+    }
+
+    Ok(())
+}
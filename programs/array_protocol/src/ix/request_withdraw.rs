@@ -0,0 +1,94 @@
+use crate::controller::{
+    accrue_management_fee, is_authorized, lock_shares, UserAction, VaultError,
+};
+use crate::events::WithdrawalRequested;
+use crate::state::{Size, SupportedTokenVault, User, UserTokenVault, WithdrawalRequest};
+use anchor_lang::prelude::*;
+
+/// Accounts for `request_withdraw`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct RequestWithdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Transfer) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Account<'info, UserTokenVault>,
+
+    /// TokenVault metadata
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub protocol_token_vault: Account<'info, SupportedTokenVault>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = WithdrawalRequest::SIZE,
+        seeds = [b"withdrawal_request".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for `request_withdraw`.
+/// Queues a withdrawal of `amount`, executable after the vault's withdrawal delay. Like
+/// `withdraw_spl`, it never checks the pause flags.
+pub fn handle_request_withdraw(
+    ctx: Context<RequestWithdraw>,
+    vault_index: u16,
+    amount: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let vault = &mut ctx.accounts.protocol_token_vault;
+    let user_token_vault = &mut ctx.accounts.user_token_vault;
+    accrue_management_fee(vault, user_token_vault, now)?;
+    let (shares, principal) = lock_shares(vault, user_token_vault, amount)?;
+
+    let executable_at = now
+        .checked_add(vault.withdrawal_delay)
+        .ok_or(VaultError::Overflow)?;
+
+    let request = &mut ctx.accounts.withdrawal_request;
+    request.user = ctx.accounts.user_state.key();
+    request.token_vault_index = vault_index;
+    request.amount = amount;
+    request.shares = shares;
+    request.principal = principal;
+    request.requested_at = now;
+    request.executable_at = executable_at;
+
+    msg!(
+        "Requested withdrawal of {} for {} shares, executable at {}",
+        amount,
+        shares,
+        executable_at
+    );
+
+    emit_cpi!(WithdrawalRequested {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        amount,
+        shares,
+        executable_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::controller::resize_account;
+use crate::events::AccountResized;
+use crate::state::{Size, SupportedTokenVault};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+/// Accounts for `resize_token_vault`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResizeTokenVault<'info> {
+    /// Pays the rent for the added fields.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: owner is checked, discriminator and size are checked in the handler. It can't be
+    /// an `Account<SupportedTokenVault>` until it has been resized.
+    #[account(mut, owner = crate::ID)]
+    pub token_vault: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for `resize_token_vault`.
+/// Grows a `SupportedTokenVault` created before deposit caps or the withdrawal delay were added
+/// to the current layout. The new fields start at zero, which leaves the caps unlimited and
+/// withdrawals instant. Anyone may call this.
+pub fn handle_resize_token_vault(ctx: Context<ResizeTokenVault>) -> Result<()> {
+    let token_vault = ctx.accounts.token_vault.to_account_info();
+    resize_account(
+        &token_vault,
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        SupportedTokenVault::DISCRIMINATOR,
+        SupportedTokenVault::SIZE,
+    )?;
+
+    msg!(
+        "Resized token vault {} to {} bytes",
+        token_vault.key(),
+        SupportedTokenVault::SIZE
+    );
+
+    emit_cpi!(AccountResized {
+        account: token_vault.key(),
+        size: SupportedTokenVault::SIZE as u32,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::controller::resize_account;
+use crate::events::AccountResized;
+use crate::state::{Size, UserTokenVault};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+/// Accounts for `resize_user_token_vault`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResizeUserTokenVault<'info> {
+    /// Pays the rent for the added fields.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: owner is checked, discriminator and size are checked in the handler. It can't be
+    /// an `Account<UserTokenVault>` until it has been resized.
+    #[account(mut, owner = crate::ID)]
+    pub user_token_vault: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for `resize_user_token_vault`.
+/// Grows a `UserTokenVault` created before shares and fees were added to the current layout.
+/// The new fields start at zero, and the shares are minted 1:1 against the principal the first
+/// time the vault is used. Anyone may call this.
+pub fn handle_resize_user_token_vault(ctx: Context<ResizeUserTokenVault>) -> Result<()> {
+    let user_token_vault = ctx.accounts.user_token_vault.to_account_info();
+    resize_account(
+        &user_token_vault,
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        UserTokenVault::DISCRIMINATOR,
+        UserTokenVault::SIZE,
+    )?;
+
+    msg!(
+        "Resized user vault {} to {} bytes",
+        user_token_vault.key(),
+        UserTokenVault::SIZE
+    );
+
+    emit_cpi!(AccountResized {
+        account: user_token_vault.key(),
+        size: UserTokenVault::SIZE as u32,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use crate::controller::{
+    drift_delegate, drift_user_address, drift_user_stats_address, drift_user_sub_account_id,
+    is_authorized, UserAction, VaultError,
+};
+use crate::events::{DelegateUpdated, DriftDelegateUpdated};
+use crate::state::{ProgramState, User, DELEGATE_PERMISSION_ALL, PAUSE_PROTOCOLS};
+use crate::{drift, get_user_seeds};
+use anchor_lang::prelude::*;
+use drift::accounts::UserStats;
+use drift::program::Drift;
+
+/// Accounts for `set_delegate`.
+/// Every Drift user of the account is passed as a remaining account, so their Drift delegate
+/// follows the new delegate.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::ManageAccount) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump
+    )]
+    pub state: Box<Account<'info, ProgramState>>,
+
+    /// CHECK: Drift user stats of `user_state`, checked against its derived address. Empty while
+    /// the user has no Drift accounts, otherwise it tells how many Drift users must be passed.
+    #[account(address = drift_user_stats_address(&user_state.key()) @ VaultError::InvalidDriftAccount)]
+    pub drift_user_stats: AccountInfo<'info>,
+
+    /// Drift Program, required once the user has Drift accounts
+    pub drift_program: Option<Program<'info, Drift>>,
+}
+
+/// Handler for `set_delegate`.
+/// Replaces the user's delegate and its permissions. Passing `Pubkey::default()` revokes it. The
+/// Drift delegate of every Drift sub-account is updated to match, unless protocols are paused.
+pub fn handle_set_delegate<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, SetDelegate<'info>>,
+    delegate: Pubkey,
+    permissions: u8,
+) -> Result<()> {
+    require!(
+        permissions & !DELEGATE_PERMISSION_ALL == 0,
+        VaultError::InvalidDelegatePermissions
+    );
+
+    let user_state = &mut ctx.accounts.user_state;
+    user_state.delegate = delegate;
+    user_state.delegate_permissions = if delegate == Pubkey::default() {
+        0
+    } else {
+        permissions
+    };
+
+    msg!(
+        "Delegate set to {} with permissions {:#04x}",
+        user_state.delegate,
+        user_state.delegate_permissions
+    );
+
+    emit_cpi!(DelegateUpdated {
+        user: user_state.key(),
+        delegate: user_state.delegate,
+        permissions: user_state.delegate_permissions,
+    });
+
+    // Keep the Drift delegate of every sub-account in step
+    if ctx.accounts.drift_user_stats.data_is_empty() {
+        return Ok(());
+    }
+    // The delegate can still be changed or revoked while protocols are paused. The Drift
+    // delegates are brought in step with `update_drift_delegate` once they are unpaused.
+    if ctx.accounts.state.paused & PAUSE_PROTOCOLS != 0 {
+        msg!("Protocols are paused, Drift delegates left unchanged");
+        return Ok(());
+    }
+    let user_stats =
+        UserStats::try_deserialize(&mut &ctx.accounts.drift_user_stats.data.borrow()[..])?;
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        user_stats.number_of_sub_accounts as usize,
+        VaultError::MissingProtocolAccounts
+    );
+    let drift_program = ctx
+        .accounts
+        .drift_program
+        .as_ref()
+        .ok_or(VaultError::MissingProtocolAccounts)?
+        .to_account_info();
+
+    let user_key = ctx.accounts.user_state.key();
+    let drift_delegate = drift_delegate(&ctx.accounts.user_state);
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    for (i, drift_user) in ctx.remaining_accounts.iter().enumerate() {
+        let sub_account_id = drift_user_sub_account_id(&drift_user.try_borrow_data()?)?;
+        require_keys_eq!(
+            drift_user.key(),
+            drift_user_address(&user_key, sub_account_id),
+            VaultError::InvalidDriftAccount
+        );
+        require!(
+            !ctx.remaining_accounts[..i]
+                .iter()
+                .any(|other| other.key == drift_user.key),
+            VaultError::InvalidDriftAccount
+        );
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            drift_program.clone(),
+            drift::cpi::accounts::UpdateUserDelegate {
+                user: drift_user.clone(),
+                authority: ctx.accounts.user_state.to_account_info(),
+            },
+            signer_seeds,
+        );
+        drift::cpi::update_user_delegate(cpi_ctx, sub_account_id, drift_delegate)?;
+
+        emit_cpi!(DriftDelegateUpdated {
+            user: user_key,
+            drift_user: drift_user.key(),
+            delegate: drift_delegate,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use crate::controller::VaultError;
+use crate::events::DepositCapsUpdated;
+use crate::state::{ProgramState, SupportedTokenVault};
+use anchor_lang::prelude::*;
+
+/// Accounts for `set_deposit_caps`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct SetDepositCaps<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, SupportedTokenVault>,
+}
+
+/// Handler for `set_deposit_caps`.
+/// A cap of 0 removes it. Lowering a cap below current deposits only blocks new deposits.
+pub fn handle_set_deposit_caps(
+    ctx: Context<SetDepositCaps>,
+    _vault_index: u16,
+    max_total_deposits: u64,
+    max_user_deposits: u64,
+) -> Result<()> {
+    let token_vault = &mut ctx.accounts.token_vault;
+    token_vault.max_total_deposits = max_total_deposits;
+    token_vault.max_user_deposits = max_user_deposits;
+
+    msg!(
+        "Vault {} deposit caps set: total {}, per user {}",
+        token_vault.token_vault_index,
+        max_total_deposits,
+        max_user_deposits
+    );
+
+    emit_cpi!(DepositCapsUpdated {
+        vault_index: token_vault.token_vault_index,
+        max_total_deposits,
+        max_user_deposits,
+    });
+
+    Ok(())
+}
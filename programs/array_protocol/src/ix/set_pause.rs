@@ -0,0 +1,36 @@
+use crate::controller::VaultError;
+use crate::events::PauseUpdated;
+use crate::state::{ProgramState, PAUSE_ALL};
+use anchor_lang::prelude::*;
+
+/// Accounts for `set_pause`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin
+    )]
+    pub state: Account<'info, ProgramState>,
+}
+
+/// Handler for `set_pause`.
+/// Replaces the global pause flags, `0` resumes everything.
+pub fn handle_set_pause(ctx: Context<SetPause>, paused: u8) -> Result<()> {
+    require!(paused & !PAUSE_ALL == 0, VaultError::InvalidPauseFlags);
+
+    ctx.accounts.state.paused = paused;
+
+    msg!("Global pause flags set to {:#04x}", paused);
+
+    emit_cpi!(PauseUpdated {
+        vault_index: None,
+        paused,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::controller::VaultError;
+use crate::events::TreasuryUpdated;
+use crate::state::ProgramState;
+use anchor_lang::prelude::*;
+
+/// Accounts for `set_treasury`.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin
+    )]
+    pub state: Account<'info, ProgramState>,
+}
+
+/// Handler for `set_treasury`.
+/// Records the owner of the token accounts that protocol fees are collected to.
+pub fn handle_set_treasury(ctx: Context<SetTreasury>, treasury: Pubkey) -> Result<()> {
+    ctx.accounts.state.treasury = treasury;
+
+    msg!("Treasury set to {}", treasury);
+
+    emit_cpi!(TreasuryUpdated { treasury });
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::controller::VaultError;
+use crate::events::VaultFeesUpdated;
+use crate::state::{
+    ProgramState, SupportedTokenVault, MAX_MANAGEMENT_FEE_BPS, MAX_PERFORMANCE_FEE_BPS,
+};
+use anchor_lang::prelude::*;
+
+/// Accounts for `set_vault_fees`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct SetVaultFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, SupportedTokenVault>,
+}
+
+/// Handler for `set_vault_fees`.
+/// New rates apply from each user's next accrual; management fees already owed are not
+/// recomputed.
+pub fn handle_set_vault_fees(
+    ctx: Context<SetVaultFees>,
+    _vault_index: u16,
+    performance_fee_bps: u16,
+    management_fee_bps: u16,
+) -> Result<()> {
+    require!(
+        performance_fee_bps <= MAX_PERFORMANCE_FEE_BPS,
+        VaultError::FeeTooHigh
+    );
+    require!(
+        management_fee_bps <= MAX_MANAGEMENT_FEE_BPS,
+        VaultError::FeeTooHigh
+    );
+
+    let token_vault = &mut ctx.accounts.token_vault;
+    token_vault.performance_fee_bps = performance_fee_bps;
+    token_vault.management_fee_bps = management_fee_bps;
+
+    msg!(
+        "Vault {} fees set: performance {} bps, management {} bps/year",
+        token_vault.token_vault_index,
+        performance_fee_bps,
+        management_fee_bps
+    );
+
+    emit_cpi!(VaultFeesUpdated {
+        vault_index: token_vault.token_vault_index,
+        performance_fee_bps,
+        management_fee_bps,
+    });
+
+    Ok(())
+}
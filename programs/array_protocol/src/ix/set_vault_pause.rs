@@ -0,0 +1,52 @@
+use crate::controller::VaultError;
+use crate::events::PauseUpdated;
+use crate::state::{ProgramState, SupportedTokenVault, PAUSE_ALL};
+use anchor_lang::prelude::*;
+
+/// Accounts for `set_vault_pause`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct SetVaultPause<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, SupportedTokenVault>,
+}
+
+/// Handler for `set_vault_pause`.
+/// Replaces the pause flags of a single vault, on top of the global ones.
+pub fn handle_set_vault_pause(
+    ctx: Context<SetVaultPause>,
+    _vault_index: u16,
+    paused: u8,
+) -> Result<()> {
+    require!(paused & !PAUSE_ALL == 0, VaultError::InvalidPauseFlags);
+
+    let token_vault = &mut ctx.accounts.token_vault;
+    token_vault.paused = paused;
+
+    msg!(
+        "Vault {} pause flags set to {:#04x}",
+        token_vault.token_vault_index,
+        paused
+    );
+
+    emit_cpi!(PauseUpdated {
+        vault_index: Some(token_vault.token_vault_index),
+        paused,
+    });
+
+    Ok(())
+}
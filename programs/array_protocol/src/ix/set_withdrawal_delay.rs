@@ -0,0 +1,55 @@
+use crate::controller::VaultError;
+use crate::events::WithdrawalDelayUpdated;
+use crate::state::{ProgramState, SupportedTokenVault, MAX_WITHDRAWAL_DELAY};
+use anchor_lang::prelude::*;
+
+/// Accounts for `set_withdrawal_delay`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct SetWithdrawalDelay<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"array_program_state".as_ref()],
+        bump,
+        has_one = admin @ VaultError::UnauthorizedAdmin
+    )]
+    pub state: Account<'info, ProgramState>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, SupportedTokenVault>,
+}
+
+/// Handler for `set_withdrawal_delay`.
+/// Requests already queued keep the delay they were made with.
+pub fn handle_set_withdrawal_delay(
+    ctx: Context<SetWithdrawalDelay>,
+    _vault_index: u16,
+    withdrawal_delay: i64,
+) -> Result<()> {
+    require!(
+        (0..=MAX_WITHDRAWAL_DELAY).contains(&withdrawal_delay),
+        VaultError::InvalidWithdrawalDelay
+    );
+
+    let token_vault = &mut ctx.accounts.token_vault;
+    token_vault.withdrawal_delay = withdrawal_delay;
+
+    msg!(
+        "Vault {} withdrawal delay set to {}s",
+        token_vault.token_vault_index,
+        withdrawal_delay
+    );
+
+    emit_cpi!(WithdrawalDelayUpdated {
+        vault_index: token_vault.token_vault_index,
+        withdrawal_delay,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use crate::controller::{
+    accrue_management_fee, charge_performance_fee, is_authorized, is_native_mint, withdraw_shares,
+    UserAction, VaultError,
+};
+use crate::events::Withdrawn;
+use crate::get_user_seeds;
+use crate::state::{SupportedTokenVault, User, UserTokenVault};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+/// Accounts for `withdraw_sol`.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_index: u16)]
+pub struct WithdrawSol<'info> {
+    /// Receives the unwrapped lamports
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        owner = token_program.key(),
+        constraint = is_native_mint(&token_vault_mint.key()) @ VaultError::NotNativeMint
+    )]
+    pub token_vault_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Transfer) @ VaultError::UnauthorizedUser
+    )]
+    pub user_state: Account<'info, User>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault_account".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+        token::mint = token_vault_mint,
+        token::authority = user_state,
+        token::token_program = token_program
+    )]
+    pub user_vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Temporary wSOL account, closed to the signer in the same instruction to unwrap
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"unwrap_sol".as_ref(), user_state.key().as_ref()],
+        bump,
+        token::mint = token_vault_mint,
+        token::authority = user_state,
+        token::token_program = token_program
+    )]
+    pub unwrap_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_vault".as_ref(), user_state.key().as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub user_token_vault: Account<'info, UserTokenVault>,
+
+    /// TokenVault metadata
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), vault_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub protocol_token_vault: Account<'info, SupportedTokenVault>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Handler for `withdraw_sol`.
+/// Withdraws like `withdraw_spl`, but unwraps to the signer's system account: the tokens go to a
+/// temporary wSOL account which is then closed to the signer. Never checks the pause flags.
+pub fn handle_withdraw_sol(ctx: Context<WithdrawSol>, vault_index: u16, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.protocol_token_vault.withdrawal_delay == 0,
+        VaultError::WithdrawalDelayActive
+    );
+
+    // 1) Charge fees and burn the shares backing the withdrawn amount
+    let vault = &mut ctx.accounts.protocol_token_vault;
+    let user_token_vault = &mut ctx.accounts.user_token_vault;
+    accrue_management_fee(vault, user_token_vault, Clock::get()?.unix_timestamp)?;
+    let (shares, principal) = withdraw_shares(vault, user_token_vault, amount)?;
+    let fee = charge_performance_fee(vault, user_token_vault, amount, principal)?;
+    msg!(
+        "Withdrew {} lamports for {} shares, fee {}",
+        amount,
+        shares,
+        fee
+    );
+
+    let seeds = get_user_seeds(
+        &ctx.accounts.user_state.authority,
+        &ctx.accounts.user_state.bump,
+    );
+    let signer_seeds = &[&seeds[..]];
+
+    // 2) Move the tokens to the temporary account, the fee stays behind until collected
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            mint: ctx.accounts.token_vault_mint.to_account_info(),
+            from: ctx.accounts.user_vault_token_account.to_account_info(),
+            to: ctx.accounts.unwrap_account.to_account_info(),
+            authority: ctx.accounts.user_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    anchor_spl::token_interface::transfer_checked(
+        cpi_ctx,
+        amount - fee,
+        ctx.accounts.token_vault_mint.decimals,
+    )?;
+
+    // 3) Close it, which hands the wrapped lamports and its rent to the signer
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.unwrap_account.to_account_info(),
+            destination: ctx.accounts.signer.to_account_info(),
+            authority: ctx.accounts.user_state.to_account_info(),
+        },
+        signer_seeds,
+    );
+    anchor_spl::token_interface::close_account(cpi_ctx)?;
+
+    emit_cpi!(Withdrawn {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        amount,
+        fee,
+        shares,
+        post_balance: ctx.accounts.user_token_vault.balance,
+    });
+
+    Ok(())
+}
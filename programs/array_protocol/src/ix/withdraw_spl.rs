@@ -1,9 +1,14 @@
+use crate::controller::{
+    accrue_management_fee, charge_performance_fee, is_authorized, withdraw_shares, UserAction,
+    VaultError,
+};
+use crate::events::Withdrawn;
 use crate::get_user_seeds;
 use crate::state::{ProgramState, SupportedTokenVault, User, UserTokenVault};
 use anchor_lang::prelude::*;
-use anchor_spl::token::TransferChecked;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(vault_index: u16)]
 pub struct WithdrawSpl<'info> {
@@ -13,7 +18,12 @@ pub struct WithdrawSpl<'info> {
     #[account(owner = token_program.key())]
     pub token_vault_mint: InterfaceAccount<'info, Mint>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"user", user_state.authority.as_ref()],
+        bump = user_state.bump,
+        constraint = is_authorized(&user_state, &signer.key(), UserAction::Transfer) @ VaultError::UnauthorizedUser
+    )]
     pub user_state: Account<'info, User>,
 
     #[account(
@@ -58,12 +68,14 @@ pub struct WithdrawSpl<'info> {
     pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handle_withdraw_spl(
-    ctx: Context<WithdrawSpl>,
-    _vault_index: u16,
-    amount: u64,
-) -> Result<()> {
-    // let token_vault = ctx.accounts.token_vault.load()?;
+/// Never checks the pause flags: moving idle funds back to the user's wallet is the exit that
+/// stays open while the protocol is paused. Vaults with a withdrawal delay go through
+/// `request_withdraw` instead.
+pub fn handle_withdraw_spl(ctx: Context<WithdrawSpl>, vault_index: u16, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.protocol_token_vault.withdrawal_delay == 0,
+        VaultError::WithdrawalDelayActive
+    );
 
     let seeds = get_user_seeds(
         &ctx.accounts.user_state.authority,
@@ -71,8 +83,16 @@ pub fn handle_withdraw_spl(
     );
     let signer_seeds = &[&seeds[..]];
 
+    // 1) Charge fees and burn the shares backing the withdrawn amount
+    let vault = &mut ctx.accounts.protocol_token_vault;
+    let user_token_vault = &mut ctx.accounts.user_token_vault;
+    accrue_management_fee(vault, user_token_vault, Clock::get()?.unix_timestamp)?;
+    let (shares, principal) = withdraw_shares(vault, user_token_vault, amount)?;
+    let fee = charge_performance_fee(vault, user_token_vault, amount, principal)?;
+    msg!("Withdrew {} for {} shares, fee {}", amount, shares, fee);
+
     let decimals = ctx.accounts.token_vault_mint.decimals;
-    // 1) Transfer from vault -> user
+    // 2) Transfer from vault -> user, the fee stays behind until collected
     let cpi_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         TransferChecked {
@@ -83,22 +103,16 @@ pub fn handle_withdraw_spl(
         },
         signer_seeds,
     );
-    anchor_spl::token::transfer_checked(cpi_ctx, amount, decimals)?;
-
-    // 2) Subtract from vault global balance
-    let vault_state = &mut ctx.accounts.protocol_token_vault;
-    vault_state.balance = vault_state
-        .balance
-        .checked_sub(amount as u128)
-        .ok_or(crate::controller::errors::VaultError::Underflow)?;
-
-    // 3) Update user position
-
-    let user_token_vault = &mut ctx.accounts.user_token_vault;
-    user_token_vault.deposited_amount = user_token_vault
-        .deposited_amount
-        .checked_sub(amount as u128)
-        .ok_or(crate::controller::errors::VaultError::Underflow)?;
+    anchor_spl::token_interface::transfer_checked(cpi_ctx, amount - fee, decimals)?;
+
+    emit_cpi!(Withdrawn {
+        user: ctx.accounts.user_state.key(),
+        vault_index,
+        amount,
+        fee,
+        shares,
+        post_balance: ctx.accounts.user_token_vault.balance,
+    });
 
     Ok(())
 }
@@ -1,4 +1,7 @@
+#![allow(clippy::too_many_arguments)]
+
 use crate::ix::*;
+use crate::state::RegisteredMarket;
 use anchor_lang::prelude::*;
 
 declare_id!("5jNZph2CQjoQcaru3fjkDvXDmMGpnrNAG8CmTyaTdnm9");
@@ -28,6 +31,101 @@ pub mod array_protocol {
         handle_init_token_vault(ctx)
     }
 
+    /// Proposes a new admin, who must call `accept_admin` to take over.
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        handle_propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        handle_accept_admin(ctx)
+    }
+
+    /// Sets the owner of the token accounts protocol fees are collected to.
+    pub fn set_treasury(ctx: Context<SetTreasury>, treasury: Pubkey) -> Result<()> {
+        handle_set_treasury(ctx, treasury)
+    }
+
+    /// Sets the performance and management fee rates of a vault, within the hard caps.
+    pub fn set_vault_fees(
+        ctx: Context<SetVaultFees>,
+        vault_index: u16,
+        performance_fee_bps: u16,
+        management_fee_bps: u16,
+    ) -> Result<()> {
+        handle_set_vault_fees(ctx, vault_index, performance_fee_bps, management_fee_bps)
+    }
+
+    /// Sets the global `PAUSE_*` flags. Withdrawals to the user's wallet are never paused.
+    pub fn set_pause(ctx: Context<SetPause>, paused: u8) -> Result<()> {
+        handle_set_pause(ctx, paused)
+    }
+
+    /// Sets the `PAUSE_*` flags of a single vault.
+    pub fn set_vault_pause(
+        ctx: Context<SetVaultPause>,
+        vault_index: u16,
+        paused: u8,
+    ) -> Result<()> {
+        handle_set_vault_pause(ctx, vault_index, paused)
+    }
+
+    /// Sets the total and per-user deposit caps of a vault, 0 for unlimited.
+    pub fn set_deposit_caps(
+        ctx: Context<SetDepositCaps>,
+        vault_index: u16,
+        max_total_deposits: u64,
+        max_user_deposits: u64,
+    ) -> Result<()> {
+        handle_set_deposit_caps(ctx, vault_index, max_total_deposits, max_user_deposits)
+    }
+
+    /// Grows a vault created before deposit caps or the withdrawal delay to the current layout,
+    /// the signer pays the rent.
+    pub fn resize_token_vault(ctx: Context<ResizeTokenVault>) -> Result<()> {
+        handle_resize_token_vault(ctx)
+    }
+
+    /// Sets the delay in seconds between requesting and executing a withdrawal, 0 for instant
+    /// withdrawals.
+    pub fn set_withdrawal_delay(
+        ctx: Context<SetWithdrawalDelay>,
+        vault_index: u16,
+        withdrawal_delay: i64,
+    ) -> Result<()> {
+        handle_set_withdrawal_delay(ctx, vault_index, withdrawal_delay)
+    }
+
+    /// Creates the empty protocol registry of a vault initialized before registries existed.
+    pub fn init_protocol_registry(
+        ctx: Context<InitProtocolRegistry>,
+        vault_index: u16,
+    ) -> Result<()> {
+        handle_init_protocol_registry(ctx, vault_index)
+    }
+
+    /// Allows deposits from `vault_index` into `market`.
+    pub fn add_registry_market(
+        ctx: Context<UpdateRegistry>,
+        vault_index: u16,
+        market: RegisteredMarket,
+    ) -> Result<()> {
+        handle_add_registry_market(ctx, vault_index, market)
+    }
+
+    /// Stops new deposits from `vault_index` into `market`.
+    pub fn remove_registry_market(
+        ctx: Context<UpdateRegistry>,
+        vault_index: u16,
+        market: RegisteredMarket,
+    ) -> Result<()> {
+        handle_remove_registry_market(ctx, vault_index, market)
+    }
+
+    /// Sends a user's accrued fees for `vault_index` to the treasury.
+    pub fn collect_fees(ctx: Context<CollectFees>, vault_index: u16) -> Result<()> {
+        handle_collect_fees(ctx, vault_index)
+    }
+
     /// Initializes a TokenVault + SPL token account.
     pub fn init_user_token_vault(ctx: Context<InitUserTokenVault>, vault_index: u16) -> Result<()> {
         handle_init_user_token_vault(ctx, vault_index)
@@ -43,6 +141,76 @@ pub mod array_protocol {
         handle_withdraw_spl(ctx, vault_index, amount)
     }
 
+    /// Closes an empty user vault and its token account, returning the rent to the authority.
+    pub fn close_user_token_vault(
+        ctx: Context<CloseUserTokenVault>,
+        vault_index: u16,
+    ) -> Result<()> {
+        handle_close_user_token_vault(ctx, vault_index)
+    }
+
+    /// Grows a user vault created before shares and fees to the current layout, the signer pays
+    /// the rent.
+    pub fn resize_user_token_vault(ctx: Context<ResizeUserTokenVault>) -> Result<()> {
+        handle_resize_user_token_vault(ctx)
+    }
+
+    /// Wraps `amount` lamports from the signer and deposits them into a wSOL vault.
+    pub fn deposit_sol(ctx: Context<DepositSol>, vault_index: u16, amount: u64) -> Result<()> {
+        handle_deposit_sol(ctx, vault_index, amount)
+    }
+
+    /// Withdraws `amount` from a wSOL vault and unwraps it to the signer's system account.
+    pub fn withdraw_sol(ctx: Context<WithdrawSol>, vault_index: u16, amount: u64) -> Result<()> {
+        handle_withdraw_sol(ctx, vault_index, amount)
+    }
+
+    /// Queues a withdrawal of `amount`, executable after the vault's withdrawal delay.
+    pub fn request_withdraw(
+        ctx: Context<RequestWithdraw>,
+        vault_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        handle_request_withdraw(ctx, vault_index, amount)
+    }
+
+    /// Withdraws a queued request to the user's wallet once its delay has passed.
+    pub fn execute_withdraw(ctx: Context<ExecuteWithdraw>, vault_index: u16) -> Result<()> {
+        handle_execute_withdraw(ctx, vault_index)
+    }
+
+    /// Cancels a queued withdrawal, returning the locked shares to the user's vault.
+    pub fn cancel_withdraw(ctx: Context<CancelWithdraw>, vault_index: u16) -> Result<()> {
+        handle_cancel_withdraw(ctx, vault_index)
+    }
+
+    /// Sets the keeper allowed to move funds between the vault and protocols, or revokes it when
+    /// `delegate` is the default pubkey. Drift users passed as remaining accounts get the same
+    /// Drift delegate.
+    pub fn set_delegate<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SetDelegate<'info>>,
+        delegate: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        handle_set_delegate(ctx, delegate, permissions)
+    }
+
+    /// Adds `count` empty position slots to the user, the signer pays the rent.
+    pub fn add_user_positions(ctx: Context<AddUserPositions>, count: u8) -> Result<()> {
+        handle_add_user_positions(ctx, count)
+    }
+
+    /// Migrates a user account with a fixed array of 8 positions to the resizable layout.
+    pub fn migrate_user(ctx: Context<MigrateUser>) -> Result<()> {
+        handle_migrate_user(ctx)
+    }
+
+    /// Closes the user account once all positions are empty and all user vaults are closed,
+    /// returning the rent to the authority.
+    pub fn close_user(ctx: Context<CloseUser>) -> Result<()> {
+        handle_close_user(ctx)
+    }
+
     pub fn init_drift_user(ctx: Context<InitDriftUser>, sub_account_id: u16) -> Result<()> {
         handle_init_drift_user(ctx, sub_account_id)
     }
@@ -51,26 +219,169 @@ pub mod array_protocol {
         handle_init_drift_user_stats(ctx)
     }
 
+    /// Mirrors the user's delegate onto the Drift user of `sub_account_id`. Permissionless.
+    pub fn update_drift_delegate(
+        ctx: Context<UpdateDriftDelegate>,
+        sub_account_id: u16,
+    ) -> Result<()> {
+        handle_update_drift_delegate(ctx, sub_account_id)
+    }
+
+    /// Deposits into a Drift spot market, creating the Drift user accounts on first use.
     pub fn drift_deposit<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, DriftDeposit<'info>>,
         vault_index: u16,
+        sub_account_id: u16,
         market_index: u16,
         amount: u64,
     ) -> Result<()> {
-        handle_drift_deposit(ctx, vault_index, market_index, amount)
+        handle_drift_deposit(ctx, vault_index, sub_account_id, market_index, amount)
     }
 
     pub fn drift_withdraw<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, DriftWithdraw<'info>>,
         vault_index: u16,
+        sub_account_id: u16,
+        market_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        handle_drift_withdraw(ctx, vault_index, sub_account_id, market_index, amount)
+    }
+
+    /// Moves a Drift spot deposit between two of the user's sub-accounts.
+    pub fn transfer_drift_deposit<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, TransferDriftDeposit<'info>>,
+        vault_index: u16,
+        from_sub_account_id: u16,
+        to_sub_account_id: u16,
+        market_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        handle_transfer_drift_deposit(
+            ctx,
+            vault_index,
+            from_sub_account_id,
+            to_sub_account_id,
+            market_index,
+            amount,
+        )
+    }
+
+    /// Deletes an empty Drift sub-account, the rent goes to the authority.
+    pub fn delete_drift_user(ctx: Context<DeleteDriftUser>, sub_account_id: u16) -> Result<()> {
+        handle_delete_drift_user(ctx, sub_account_id)
+    }
+
+    /// Creates the user's stake account in the Drift insurance fund of `market_index`.
+    pub fn init_drift_insurance_fund_stake(
+        ctx: Context<InitDriftInsuranceFundStake>,
+        market_index: u16,
+    ) -> Result<()> {
+        handle_init_drift_insurance_fund_stake(ctx, market_index)
+    }
+
+    /// Stakes idle vault funds in a Drift insurance fund registered for the vault.
+    pub fn add_drift_insurance_fund_stake<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, AddDriftInsuranceFundStake<'info>>,
+        vault_index: u16,
+        market_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        handle_add_drift_insurance_fund_stake(ctx, vault_index, market_index, amount)
+    }
+
+    /// Starts the unstaking period for part of a Drift insurance fund stake.
+    pub fn request_remove_drift_insurance_fund_stake(
+        ctx: Context<RequestRemoveDriftInsuranceFundStake>,
+        vault_index: u16,
+        market_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        handle_request_remove_drift_insurance_fund_stake(ctx, vault_index, market_index, amount)
+    }
+
+    /// Pays out a Drift insurance fund stake whose unstaking period has passed.
+    pub fn remove_drift_insurance_fund_stake(
+        ctx: Context<RemoveDriftInsuranceFundStake>,
+        vault_index: u16,
+        market_index: u16,
+    ) -> Result<()> {
+        handle_remove_drift_insurance_fund_stake(ctx, vault_index, market_index)
+    }
+
+    /// Refreshes the value of the user's Drift position for `vault_index` from its spot balance.
+    /// Permissionless.
+    pub fn sync_drift_position(ctx: Context<SyncDriftPosition>, vault_index: u16) -> Result<()> {
+        handle_sync_drift_position(ctx, vault_index)
+    }
+
+    pub fn init_kamino_user_metadata(ctx: Context<InitKaminoUserMetadata>) -> Result<()> {
+        handle_init_kamino_user_metadata(ctx)
+    }
+
+    pub fn init_kamino_obligation(
+        ctx: Context<InitKaminoObligation>,
+        obligation_id: u8,
+    ) -> Result<()> {
+        handle_init_kamino_obligation(ctx, obligation_id)
+    }
+
+    pub fn init_kamino_obligation_farms(ctx: Context<InitKaminoObligationFarms>) -> Result<()> {
+        handle_init_kamino_obligation_farms(ctx)
+    }
+
+    /// Deposits `amount` of the vault's token from the user's vault into a Kamino reserve,
+    /// through the user's obligation `obligation_id`.
+    pub fn kamino_deposit<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, KaminoDeposit<'info>>,
+        vault_index: u16,
+        obligation_id: u8,
+        amount: u64,
+    ) -> Result<()> {
+        handle_kamino_deposit(ctx, vault_index, obligation_id, amount)
+    }
+
+    /// Redeems `collateral_amount` of Kamino reserve collateral from the user's obligation
+    /// `obligation_id` back into the user's vault.
+    pub fn kamino_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, KaminoWithdraw<'info>>,
+        vault_index: u16,
+        obligation_id: u8,
+        collateral_amount: u64,
+    ) -> Result<()> {
+        handle_kamino_withdraw(ctx, vault_index, obligation_id, collateral_amount)
+    }
+
+    /// Refreshes the value of the user's Kamino position for `vault_index` from its obligation
+    /// collateral. Permissionless.
+    pub fn sync_kamino_position(ctx: Context<SyncKaminoPosition>, vault_index: u16) -> Result<()> {
+        handle_sync_kamino_position(ctx, vault_index)
+    }
+
+    /// Atomically moves funds between Drift, Kamino and the idle vault.
+    pub fn rebalance<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Rebalance<'info>>,
+        vault_index: u16,
+        from: RebalanceTarget,
+        to: RebalanceTarget,
+        sub_account_id: u16,
         market_index: u16,
         amount: u64,
     ) -> Result<()> {
-        handle_drift_withdraw(ctx, vault_index, market_index, amount)
+        handle_rebalance(
+            ctx,
+            vault_index,
+            from,
+            to,
+            sub_account_id,
+            market_index,
+            amount,
+        )
     }
 }
 
 pub mod controller;
+pub mod events;
 pub mod ids;
 pub mod ix;
 pub mod state;
@@ -1,12 +1,16 @@
 pub mod program_state;
+pub mod protocol_registry;
 pub mod token_vault;
 pub mod user;
 pub mod user_token_vault;
+pub mod withdrawal_request;
 
 pub use program_state::*;
+pub use protocol_registry::*;
 pub use token_vault::*;
 pub use user::*;
 pub use user_token_vault::*;
+pub use withdrawal_request::*;
 pub trait Size {
     const SIZE: usize;
 }
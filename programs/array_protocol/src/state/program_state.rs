@@ -9,10 +9,23 @@ pub struct ProgramState {
     pub signer_pda: Pubkey,
     pub token_vault_count: u16,
     pub bump: u8,
-    pub _padding: [u8; 5],
-    pub reserved: [u8; 64],
+    /// Bitmask of `PAUSE_*` flags applied to every vault.
+    pub paused: u8,
+    pub _padding: [u8; 4],
+    /// Owner of the token accounts protocol fees are collected to, one per vault mint
+    pub treasury: Pubkey,
+    /// Admin proposed by `propose_admin`, becomes `admin` once it calls `accept_admin`
+    pub pending_admin: Pubkey,
 }
 
 impl Size for ProgramState {
     const SIZE: usize = 144;
 }
+
+/// Pauses deposits from wallets into vaults and from vaults into protocols.
+pub const PAUSE_DEPOSITS: u8 = 1 << 0;
+/// Pauses withdrawals from protocols back into vaults, and fee collection.
+pub const PAUSE_WITHDRAWALS: u8 = 1 << 1;
+/// Pauses every CPI into an integrated protocol, including account creation and position syncs.
+pub const PAUSE_PROTOCOLS: u8 = 1 << 2;
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSITS | PAUSE_WITHDRAWALS | PAUSE_PROTOCOLS;
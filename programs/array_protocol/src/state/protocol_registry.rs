@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use super::Size;
+
+pub const MAX_REGISTERED_MARKETS: usize = 16;
+
+/// Protocol markets a vault is allowed to deposit into, managed by the admin.
+/// One per `SupportedTokenVault`, at seeds = [ "protocol_registry", vault_index ].
+#[account]
+#[repr(C)]
+pub struct ProtocolRegistry {
+    pub token_vault_index: u16,
+    pub len: u8,
+    pub _padding: [u8; 5],
+    pub markets: [RegisteredMarket; MAX_REGISTERED_MARKETS],
+}
+
+impl Size for ProtocolRegistry {
+    const SIZE: usize = 8 + 2 + 1 + 5 + MAX_REGISTERED_MARKETS * RegisteredMarket::SIZE;
+}
+
+impl ProtocolRegistry {
+    pub fn markets(&self) -> &[RegisteredMarket] {
+        &self.markets[..self.len as usize]
+    }
+
+    pub fn is_registered(&self, market: &RegisteredMarket) -> bool {
+        self.markets().contains(market)
+    }
+}
+
+/// A protocol market deposits may go to.
+/// - Drift: `market` is the spot market vault, `market_index` the spot market index.
+/// - Drift insurance fund: `market` is the insurance fund vault, `market_index` the spot market
+///   index.
+/// - Kamino: `market` is the reserve, `lending_market` its lending market.
+///
+/// Fields a protocol does not use are zero.
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct RegisteredMarket {
+    /// Program ID of the protocol
+    pub protocol: Pubkey,
+    pub market: Pubkey,
+    pub lending_market: Pubkey,
+    pub market_index: u16,
+    pub _padding: [u8; 6],
+}
+
+impl Size for RegisteredMarket {
+    const SIZE: usize = 32 + 32 + 32 + 2 + 6;
+}
+
+impl RegisteredMarket {
+    pub fn drift(protocol: Pubkey, spot_market_vault: Pubkey, market_index: u16) -> Self {
+        Self {
+            protocol,
+            market: spot_market_vault,
+            market_index,
+            ..Default::default()
+        }
+    }
+
+    pub fn drift_insurance_fund(
+        protocol: Pubkey,
+        insurance_fund_vault: Pubkey,
+        market_index: u16,
+    ) -> Self {
+        Self::drift(protocol, insurance_fund_vault, market_index)
+    }
+
+    pub fn kamino(protocol: Pubkey, reserve: Pubkey, lending_market: Pubkey) -> Self {
+        Self {
+            protocol,
+            market: reserve,
+            lending_market,
+            ..Default::default()
+        }
+    }
+}
@@ -4,19 +4,54 @@ use super::Size;
 
 /// Global vault metadata stored on-chain.
 #[account]
+#[derive(Default)]
 #[repr(C)]
 pub struct SupportedTokenVault {
     /// SPL token mint (e.g. USDC) for deposits
     pub mint: Pubkey,
 
-    /// Global total deposits (128-bit for large amounts)
+    /// Global total assets owned by depositors, including synced protocol yield
+    /// (128-bit for large amounts)
     pub balance: u128,
 
     pub token_vault_index: u16,
 
-    pub _reserved: [u8; 30],
+    /// Total shares issued to depositors. Shares are priced per user against
+    /// `UserTokenVault.balance`, `balance` is the sum of those
+    pub total_shares: u128,
+
+    /// Fee on realized yield, in bps, capped by `MAX_PERFORMANCE_FEE_BPS`
+    pub performance_fee_bps: u16,
+
+    /// Yearly fee on deposited value, in bps, capped by `MAX_MANAGEMENT_FEE_BPS`
+    pub management_fee_bps: u16,
+
+    /// Fees charged to users of this vault and not yet collected to the treasury
+    pub accrued_fees: u64,
+
+    /// Bitmask of `PAUSE_*` flags applied to this vault only
+    pub paused: u8,
+
+    pub _reserved: [u8; 1],
+
+    /// Cap on `balance`, 0 for unlimited
+    pub max_total_deposits: u64,
+
+    /// Cap on each user's `UserTokenVault.deposited_amount`, 0 for unlimited
+    pub max_user_deposits: u64,
+
+    /// Seconds between `request_withdraw` and `execute_withdraw`. When set, `withdraw_spl` is
+    /// disabled and withdrawals must be queued.
+    pub withdrawal_delay: i64,
 }
 
+/// Hard cap on `SupportedTokenVault.performance_fee_bps` (30%).
+pub const MAX_PERFORMANCE_FEE_BPS: u16 = 3_000;
+/// Hard cap on `SupportedTokenVault.management_fee_bps` (5% per year).
+pub const MAX_MANAGEMENT_FEE_BPS: u16 = 500;
+/// Hard cap on `SupportedTokenVault.withdrawal_delay` (30 days).
+pub const MAX_WITHDRAWAL_DELAY: i64 = 30 * 24 * 60 * 60;
+
 impl Size for SupportedTokenVault {
-    const SIZE: usize = 88;
+    const SIZE: usize = 112;
 }
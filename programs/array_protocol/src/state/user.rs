@@ -2,23 +2,47 @@ use anchor_lang::prelude::*;
 
 use super::Size;
 
-/// Main user account, storing authority, delegate, and up to 8 positions.
+/// Main user account, storing authority, delegate, and its positions.
 #[account]
 #[repr(C)]
 pub struct User {
     pub authority: Pubkey,
     pub delegate: Pubkey,
     pub bump: u8,
-    pub _padding: [u8; 7], // Added padding to align to 8-byte boundary
-    pub positions: [Position; 8],
+    /// Bitmask of `DELEGATE_PERMISSION_*` flags granted to `delegate`.
+    pub delegate_permissions: u8,
+    pub _padding: [u8; 6], // Added padding to align to 8-byte boundary
+    /// Position slots, grown with `add_user_positions`. Empty slots are reused first.
+    pub positions: Vec<Position>,
 }
 
-impl Size for User {
-    const SIZE: usize = 8 + 32 + 32 + 1 + 7 + 8 * Position::SIZE;
+/// Position slots a new `User` starts with.
+pub const INITIAL_POSITION_SLOTS: usize = 8;
+/// Upper bound on position slots, so the account stays cheap to deserialize.
+pub const MAX_POSITION_SLOTS: usize = 64;
+
+impl User {
+    /// Account size for `slots` positions.
+    pub const fn space(slots: usize) -> usize {
+        8 + 32 + 32 + 1 + 1 + 6 + 4 + slots * Position::SIZE
+    }
+
+    /// Size of accounts created before positions became a `Vec`, with a fixed array of 8
+    /// positions in the `LEGACY_POSITION_SIZE` layout and no length prefix.
+    pub const LEGACY_SIZE: usize = 8 + 32 + 32 + 1 + 7 + 8 * LEGACY_POSITION_SIZE;
+
+    /// Offset of the positions, after the discriminator and the fixed fields.
+    pub const POSITIONS_OFFSET: usize = 8 + 32 + 32 + 1 + 1 + 6;
 }
 
+/// Delegate may create Drift accounts and move funds between the vault and Drift.
+pub const DELEGATE_PERMISSION_DRIFT: u8 = 1 << 0;
+/// Delegate may create Kamino accounts and move funds between the vault and Kamino.
+pub const DELEGATE_PERMISSION_KAMINO: u8 = 1 << 1;
+pub const DELEGATE_PERMISSION_ALL: u8 = DELEGATE_PERMISSION_DRIFT | DELEGATE_PERMISSION_KAMINO;
+
 /// A position referencing a vault_index and current token balance.
-#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, Default)]
 #[repr(C)]
 pub struct Position {
     pub user_token_vault: Pubkey,
@@ -26,24 +50,101 @@ pub struct Position {
     pub protocol: Pubkey,
     pub protocol_vault: Pubkey,
     pub deposited_amount: u64,
+    /// Token value of the position in the protocol, including accrued interest, as of
+    /// `last_synced_slot`. Deposits and withdrawals adjust it between syncs.
+    pub value: u64,
+    /// Slot of the last `sync_*_position`, 0 if never synced.
+    pub last_synced_slot: u64,
     pub vault_index: u16,
-    pub _padding: [u8; 6],
+    /// Drift sub-account holding the position, 0 for other protocols.
+    pub sub_account_id: u16,
+    /// Drift spot market index, 0 for other protocols. Positions opened before it was recorded
+    /// read 0 until their next deposit or withdrawal.
+    pub market_index: u16,
+    pub kind: PositionKind,
+    pub _padding: [u8; 1],
 }
 
-impl Default for Position {
-    fn default() -> Self {
-        Self {
-            user_token_vault: Pubkey::default(),
-            user_token_vault_account: Pubkey::default(),
-            protocol: Pubkey::default(),
-            protocol_vault: Pubkey::default(),
-            deposited_amount: 0,
-            vault_index: 0,
-            _padding: [0; 6],
-        }
+impl Size for Position {
+    const SIZE: usize = 32 + 32 + 32 + 32 + 8 + 8 + 8 + 2 + 2 + 2 + 1 + 1;
+}
+
+/// Size of a `Position` in accounts created before it tracked a synced value: the four keys,
+/// `deposited_amount`, `vault_index` and 6 bytes of padding.
+pub const LEGACY_POSITION_SIZE: usize = 32 + 32 + 32 + 32 + 8 + 2 + 6;
+
+/// What a position holds. Positions of different kinds in the same protocol vault use separate
+/// slots.
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PositionKind {
+    /// Lent out in a Drift spot market or a Kamino reserve.
+    #[default]
+    Lending,
+    /// Staked in a Drift insurance fund. `protocol_vault` is the insurance fund vault.
+    InsuranceFundStake,
+    /// Requested for removal from a Drift insurance fund and waiting out its unstaking period.
+    /// `last_synced_slot` is the slot of the request.
+    InsuranceFundUnstake,
+}
+
+impl Position {
+    /// Reads a position stored in the `LEGACY_POSITION_SIZE` layout. Its `value` starts at the
+    /// deposited amount, as if it had been synced when opened, so the first sync only books the
+    /// interest earned since. Fields the layout didn't have are 0.
+    pub fn from_legacy(data: &[u8]) -> Result<Self> {
+        require_eq!(
+            data.len(),
+            LEGACY_POSITION_SIZE,
+            crate::controller::VaultError::NotLegacyUser
+        );
+        let deposited_amount = u64::try_from_slice(&data[128..136])?;
+        Ok(Self {
+            user_token_vault: Pubkey::try_from_slice(&data[0..32])?,
+            user_token_vault_account: Pubkey::try_from_slice(&data[32..64])?,
+            protocol: Pubkey::try_from_slice(&data[64..96])?,
+            protocol_vault: Pubkey::try_from_slice(&data[96..128])?,
+            deposited_amount,
+            value: deposited_amount,
+            vault_index: u16::try_from_slice(&data[136..138])?,
+            ..Default::default()
+        })
+    }
+
+    /// Whether the slot is in use and still holds funds in the protocol. A position whose
+    /// `value` still shows interest after withdrawing the principal needs a sync to empty it.
+    pub fn is_open(&self) -> bool {
+        self.user_token_vault != Pubkey::default() && (self.deposited_amount > 0 || self.value > 0)
     }
 }
 
-impl Size for Position {
-    const SIZE: usize = 32 + 32 + 32 + 32 + 8 + 2 + 6;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_legacy_positions() {
+        let vault = Pubkey::new_unique();
+        let mut data = vec![0u8; LEGACY_POSITION_SIZE];
+        data[0..32].copy_from_slice(vault.as_ref());
+        data[128..136].copy_from_slice(&500u64.to_le_bytes());
+        data[136..138].copy_from_slice(&3u16.to_le_bytes());
+
+        let pos = Position::from_legacy(&data).unwrap();
+        assert_eq!(pos.user_token_vault, vault);
+        assert_eq!(pos.deposited_amount, 500);
+        assert_eq!(pos.value, 500);
+        assert_eq!(pos.vault_index, 3);
+        assert_eq!(pos.kind, PositionKind::Lending);
+        assert!(Position::from_legacy(&data[..100]).is_err());
+    }
+
+    #[test]
+    fn legacy_layout_matches_deployed_accounts() {
+        assert_eq!(LEGACY_POSITION_SIZE, 144);
+        assert_eq!(User::LEGACY_SIZE, 1232);
+        assert_eq!(
+            (User::LEGACY_SIZE - User::POSITIONS_OFFSET) % LEGACY_POSITION_SIZE,
+            0
+        );
+    }
 }
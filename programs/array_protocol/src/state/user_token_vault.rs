@@ -2,19 +2,33 @@ use super::Size;
 use anchor_lang::prelude::*;
 
 #[account]
+#[derive(Default)]
 #[repr(C)]
 pub struct UserTokenVault {
     /// SPL token mint (e.g. USDC) for deposits
     pub mint: Pubkey,
 
-    /// User total deposits (128-bit for large amounts)
+    /// User principal still deposited, excluding yield (128-bit for large amounts)
     pub deposited_amount: u128,
 
     pub token_vault_index: u16,
 
-    pub _reserved: [u8; 30],
+    /// Shares owned by the user, each worth `balance / shares`
+    pub shares: u128,
+
+    /// Fees charged to this user, held in the user vault token account until collected
+    pub accrued_fees: u64,
+
+    /// Unix timestamp up to which the management fee has been charged
+    pub last_fee_accrual_ts: i64,
+
+    /// Assets the user's shares are redeemable for: deposits, idle or deployed, plus protocol
+    /// yield booked by position syncs and withdrawals, minus fees
+    pub balance: u64,
+
+    pub _reserved: [u8; 6],
 }
 
 impl Size for UserTokenVault {
-    const SIZE: usize = 88;
+    const SIZE: usize = 104;
 }
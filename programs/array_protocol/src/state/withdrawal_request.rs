@@ -0,0 +1,33 @@
+use super::Size;
+use anchor_lang::prelude::*;
+
+/// A queued withdrawal, one per user and vault. Its shares, principal and amount are held out of
+/// the `UserTokenVault` until it is executed or cancelled.
+#[account]
+#[repr(C)]
+pub struct WithdrawalRequest {
+    /// The `User` account that made the request
+    pub user: Pubkey,
+
+    pub token_vault_index: u16,
+
+    pub _padding: [u8; 6],
+
+    /// Tokens to withdraw, before fees
+    pub amount: u64,
+
+    /// Shares locked out of the user's vault
+    pub shares: u128,
+
+    /// Principal locked out of `UserTokenVault.deposited_amount`
+    pub principal: u128,
+
+    pub requested_at: i64,
+
+    /// Unix timestamp from which `execute_withdraw` is allowed
+    pub executable_at: i64,
+}
+
+impl Size for WithdrawalRequest {
+    const SIZE: usize = 8 + 32 + 2 + 6 + 8 + 16 + 16 + 8 + 8;
+}
@@ -33,7 +33,7 @@ describe("array-protocol: Initialize Supported Token Vaults", () => {
 
         await program.methods
             .initSupportedTokenVault()
-            .accounts({
+            .accountsPartial({
                 admin: provider.wallet.publicKey, // Using provider as admin for test
                 state: programStatePda,
                 tokenVaultMint: USDC_MINT,
@@ -118,6 +118,7 @@ describe("array-protocol: USDC Vault", () => {
                     userState: userStatePda,
                     state: programStatePda,
                     arraySigner: programSignerPda,
+                    tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
                 })
                 .rpc();
 
@@ -157,6 +157,21 @@ describe("array-protocol: Drift Deposit", () => {
         console.log("Drift user initialized successfully");
     });
 
+    it("should register the drift spot market for the vault", async function () {
+        await program.methods
+            .addRegistryMarket(vaultIndex, {
+                protocol: DRIFT_PROGRAM_ID,
+                market: driftSpotMarketVaultPda,
+                lendingMarket: anchor.web3.PublicKey.default,
+                marketIndex: driftMarketIndex,
+                padding: [0, 0, 0, 0, 0, 0],
+            })
+            .accounts({
+                admin: provider.wallet.publicKey,
+            })
+            .rpc();
+    });
+
     it("should execute a drift deposit", async function () {
         // Check if user vault has enough USDC for the deposit
         try {
@@ -210,7 +225,7 @@ describe("array-protocol: Drift Deposit", () => {
             console.log("Drift remaining accounts:", driftRemainingAccounts);
 
             await program.methods
-                .driftDeposit(vaultIndex, marketIndex, new anchor.BN(depositAmount))
+                .driftDeposit(vaultIndex, 0, marketIndex, new anchor.BN(depositAmount))
                 .accounts({
                     signer: provider.wallet.publicKey,
                     tokenVaultMint: USDC_MINT,
@@ -230,11 +245,60 @@ describe("array-protocol: Drift Deposit", () => {
             // Verify the state changes
             const updatedUserVaultData = await program.account.userTokenVault.fetch(userTokenVaultPda);
             console.log("User vault balance after deposit:", updatedUserVaultData.depositedAmount.toString());
+
+            const userData = await program.account.user.fetch(userStatePda);
+            const position = userData.positions.find((pos) => pos.protocolVault.equals(driftSpotMarketVaultPda));
+            expect(position.subAccountId).to.equal(0);
+            expect(position.marketIndex).to.equal(marketIndex);
         } catch (e) {
             console.error("Error checking user vault balance or executing deposit:", e);
+            throw e;
         }
     });
 
+    it("should create a new sub-account on its first drift deposit", async function () {
+        const depositAmount = 1_000_000; // 1 USDC (6 decimals)
+        const userVaultData = await program.account.userTokenVault.fetch(userTokenVaultPda);
+        if (userVaultData.depositedAmount.toNumber() < depositAmount) {
+            console.warn("Not enough USDC in user vault. Skipping sub-account deposit test.");
+            return;
+        }
+
+        const newSubAccountId = subAccountId + 1;
+        const [newDriftUserPda] = findDriftUserPDA(userStatePda, newSubAccountId, DRIFT_PROGRAM_ID);
+        expect(await provider.connection.getAccountInfo(newDriftUserPda)).to.be.null;
+
+        const driftRemainingAccounts = driftClient.getRemainingAccounts({
+            userAccounts: [],
+            writableSpotMarketIndexes: [driftMarketIndex],
+            useMarketLastSlotCache: false,
+        });
+
+        await program.methods
+            .driftDeposit(vaultIndex, newSubAccountId, driftMarketIndex, new anchor.BN(depositAmount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: USDC_MINT,
+                userState: userStatePda,
+                driftState: driftStatePda,
+                driftUser: newDriftUserPda,
+                driftUserStats: driftUserStatsPda,
+                spotMarketVault: driftSpotMarketVaultPda,
+                arraySigner: programSignerPda,
+                tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+            })
+            .remainingAccounts(driftRemainingAccounts)
+            .rpc();
+
+        expect(await provider.connection.getAccountInfo(newDriftUserPda)).to.not.be.null;
+
+        const userData = await program.account.user.fetch(userStatePda);
+        const position = userData.positions.find(
+            (pos) => pos.protocolVault.equals(driftSpotMarketVaultPda) && pos.subAccountId === newSubAccountId
+        );
+        expect(position.depositedAmount.toNumber()).to.equal(depositAmount);
+    });
+
     it("should mock a successful drift deposit", async () => {
         // For testing without an actual Drift program, this is a mock test
         // that only checks if we're constructing the accounts properly
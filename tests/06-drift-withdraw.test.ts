@@ -164,7 +164,7 @@ describe("array-protocol: Drift Withdraw", () => {
             console.log("Drift remaining accounts:", driftRemainingAccounts);
 
             await program.methods
-                .driftWithdraw(vaultIndex, driftMarketIndex, new anchor.BN(withdrawAmount))
+                .driftWithdraw(vaultIndex, 0, driftMarketIndex, new anchor.BN(withdrawAmount))
                 .accounts({
                     signer: provider.wallet.publicKey,
                     tokenVaultMint: USDC_MINT,
@@ -186,9 +186,12 @@ describe("array-protocol: Drift Withdraw", () => {
             const userVaultDataAfter = await program.account.userTokenVault.fetch(userTokenVaultPda);
             console.log(`User vault balance after withdrawal: ${userVaultDataAfter.depositedAmount.toString()} USDC units`);
 
-            // Check if the user token vault balance decreased by the withdrawn amount
-            expect(Number(userVaultDataAfter.depositedAmount)).to.be.lessThan(
-                Number(userVaultDataBefore.depositedAmount)
+            // Moving funds from Drift back to the vault does not change the user's principal or shares
+            expect(userVaultDataAfter.depositedAmount.toString()).to.equal(
+                userVaultDataBefore.depositedAmount.toString()
+            );
+            expect(userVaultDataAfter.shares.toString()).to.equal(
+                userVaultDataBefore.shares.toString()
             );
 
         } catch (e) {
@@ -197,6 +200,58 @@ describe("array-protocol: Drift Withdraw", () => {
         }
     });
 
+    it("should sync the drift position value", async () => {
+        const spotMarket = driftClient.getSpotMarketAccount(driftMarketIndex);
+
+        await program.methods
+            .syncDriftPosition(vaultIndex)
+            .accounts({
+                userState: userStatePda,
+                driftUser: driftUserPda,
+                spotMarket: spotMarket.pubkey,
+            })
+            .rpc();
+
+        const userStateData = await program.account.user.fetch(userStatePda);
+        const position = userStateData.positions.find(
+            (p) => p.vaultIndex === vaultIndex && p.protocolVault.equals(driftSpotMarketVaultPda)
+        );
+        expect(position).to.not.be.undefined;
+        expect(position!.lastSyncedSlot.toNumber()).to.be.greaterThan(0);
+        // Drift rounds the scaled balance down, so allow one unit below the principal
+        expect(position!.value.gte(position!.depositedAmount.subn(1))).to.be.true;
+    });
+
+    it("should reject withdrawing more than the position holds", async () => {
+        const userStateData = await program.account.user.fetch(userStatePda);
+        const position = userStateData.positions.find(
+            (p) => p.vaultIndex === vaultIndex && p.protocolVault.equals(driftSpotMarketVaultPda)
+        );
+        const held = anchor.BN.max(position!.value, position!.depositedAmount);
+
+        try {
+            await program.methods
+                .driftWithdraw(vaultIndex, 0, driftMarketIndex, held.addn(1))
+                .accounts({
+                    signer: provider.wallet.publicKey,
+                    tokenVaultMint: USDC_MINT,
+                    userState: userStatePda,
+                    driftState: driftStatePda,
+                    driftUser: driftUserPda,
+                    driftUserStats: driftUserStatsPda,
+                    spotMarketVault: driftSpotMarketVaultPda,
+                    arraySigner: programSignerPda,
+                    driftSigner: driftSignerPda,
+                    tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+            expect.fail("Withdrawing past the position should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal("ExceedsPositionValue");
+        }
+    });
+
     it("should mock a successful drift withdraw", async () => {
         // For testing without an actual Drift program, this is a mock test
         // that only checks if we're constructing the accounts properly
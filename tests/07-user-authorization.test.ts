@@ -0,0 +1,260 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import {
+    createAssociatedTokenAccount,
+    TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import {
+    findProgramStatePDA,
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findTokenVaultPDA,
+    findDriftUserPDA,
+    findDriftUserStatsPDA,
+    findDriftSpotMarketVaultPDA,
+    findDriftStatePDA
+} from "./utils/pda-gen";
+
+describe("array-protocol: User Authorization", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    const DRIFT_PROGRAM_ID = new anchor.web3.PublicKey("DftNc7gwihkEEwQRpu4bV89N18xpNEuBVg7YkhTZZhVo");
+
+    // Someone who is neither the authority nor the delegate of the provider's user account
+    const attacker = anchor.web3.Keypair.generate();
+
+    let programStatePda: anchor.web3.PublicKey;
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let driftStatePda: anchor.web3.PublicKey;
+    let driftUserPda: anchor.web3.PublicKey;
+    let driftUserStatsPda: anchor.web3.PublicKey;
+    let driftSpotMarketVaultPda: anchor.web3.PublicKey;
+
+    let vaultMint: anchor.web3.PublicKey;
+    let attackerTokenAccount: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+    const driftMarketIndex = 0;
+    const subAccountId = 0;
+
+    const expectUnauthorized = async (promise: Promise<string>) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal("UnauthorizedUser");
+        }
+    };
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+
+        [driftStatePda] = findDriftStatePDA(DRIFT_PROGRAM_ID);
+        [driftUserPda] = findDriftUserPDA(userStatePda, subAccountId, DRIFT_PROGRAM_ID);
+        [driftUserStatsPda] = findDriftUserStatsPDA(userStatePda, DRIFT_PROGRAM_ID);
+        [driftSpotMarketVaultPda] = findDriftSpotMarketVaultPDA(driftMarketIndex, DRIFT_PROGRAM_ID);
+
+        const signature = await provider.connection.requestAirdrop(
+            attacker.publicKey,
+            anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+
+        const [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+
+        attackerTokenAccount = await createAssociatedTokenAccount(
+            provider.connection,
+            (provider.wallet as anchor.Wallet).payer,
+            vaultMint,
+            attacker.publicKey
+        );
+    });
+
+    it("should reject init_user_token_vault from a foreign signer", async () => {
+        await expectUnauthorized(
+            program.methods
+                .initUserTokenVault(vaultIndex + 1)
+                .accounts({
+                    signer: attacker.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userState: userStatePda,
+                    state: programStatePda,
+                    arraySigner: programSignerPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([attacker])
+                .rpc()
+        );
+    });
+
+    it("should reject deposit_spl from a foreign signer", async () => {
+        await expectUnauthorized(
+            program.methods
+                .depositSpl(vaultIndex, new anchor.BN(1))
+                .accounts({
+                    signer: attacker.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userTokenAccount: attackerTokenAccount,
+                    userState: userStatePda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    arraySigner: programSignerPda,
+                })
+                .signers([attacker])
+                .rpc()
+        );
+    });
+
+    it("should reject withdraw_spl from a foreign signer", async () => {
+        await expectUnauthorized(
+            program.methods
+                .withdrawSpl(vaultIndex, new anchor.BN(1))
+                .accounts({
+                    signer: attacker.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userState: userStatePda,
+                    userTokenAccount: attackerTokenAccount,
+                    state: programStatePda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    arraySigner: programSignerPda,
+                })
+                .signers([attacker])
+                .rpc()
+        );
+    });
+
+    it("should reject init_drift_user_stats from a foreign signer", async () => {
+        await expectUnauthorized(
+            program.methods
+                .initDriftUserStats()
+                .accounts({
+                    signer: attacker.publicKey,
+                    userState: userStatePda,
+                    driftState: driftStatePda,
+                    driftUserStats: driftUserStatsPda,
+                    arraySigner: programSignerPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([attacker])
+                .rpc()
+        );
+    });
+
+    it("should reject init_drift_user from a foreign signer", async () => {
+        await expectUnauthorized(
+            program.methods
+                .initDriftUser(subAccountId + 1)
+                .accounts({
+                    signer: attacker.publicKey,
+                    userState: userStatePda,
+                    driftState: driftStatePda,
+                    driftUser: driftUserPda,
+                    driftUserStats: driftUserStatsPda,
+                    arraySigner: programSignerPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([attacker])
+                .rpc()
+        );
+    });
+
+    it("should reject drift_deposit from a foreign signer", async () => {
+        await expectUnauthorized(
+            program.methods
+                .driftDeposit(vaultIndex, 0, driftMarketIndex, new anchor.BN(1))
+                .accounts({
+                    signer: attacker.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userState: userStatePda,
+                    driftState: driftStatePda,
+                    driftUser: driftUserPda,
+                    driftUserStats: driftUserStatsPda,
+                    spotMarketVault: driftSpotMarketVaultPda,
+                    arraySigner: programSignerPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([attacker])
+                .rpc()
+        );
+    });
+
+    it("should reject drift_withdraw from a foreign signer", async () => {
+        await expectUnauthorized(
+            program.methods
+                .driftWithdraw(vaultIndex, 0, driftMarketIndex, new anchor.BN(1))
+                .accounts({
+                    signer: attacker.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userState: userStatePda,
+                    driftState: driftStatePda,
+                    driftUser: driftUserPda,
+                    driftUserStats: driftUserStatsPda,
+                    spotMarketVault: driftSpotMarketVaultPda,
+                    arraySigner: programSignerPda,
+                    driftSigner: programSignerPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([attacker])
+                .rpc()
+        );
+    });
+
+    it("should reject kamino_deposit from a foreign signer", async () => {
+        // The signer is rejected before any Kamino account is checked, any placeholder will do here
+        const placeholder = anchor.web3.Keypair.generate().publicKey;
+
+        await expectUnauthorized(
+            program.methods
+                .kaminoDeposit(vaultIndex, 0, new anchor.BN(1))
+                .accounts({
+                    signer: attacker.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userState: userStatePda,
+                    obligation: placeholder,
+                    lendingMarket: placeholder,
+                    lendingMarketAuthority: placeholder,
+                    reserve: placeholder,
+                    reserveLiquiditySupply: attackerTokenAccount,
+                    reserveCollateralMint: vaultMint,
+                    reserveDestinationDepositCollateral: attackerTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([attacker])
+                .rpc()
+        );
+    });
+
+    it("should reject kamino_withdraw from a foreign signer", async () => {
+        const placeholder = anchor.web3.Keypair.generate().publicKey;
+
+        await expectUnauthorized(
+            program.methods
+                .kaminoWithdraw(vaultIndex, 0, new anchor.BN(1))
+                .accounts({
+                    signer: attacker.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userState: userStatePda,
+                    obligation: placeholder,
+                    lendingMarket: placeholder,
+                    lendingMarketAuthority: placeholder,
+                    reserve: placeholder,
+                    reserveSourceCollateral: attackerTokenAccount,
+                    reserveCollateralMint: vaultMint,
+                    reserveLiquiditySupply: attackerTokenAccount,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([attacker])
+                .rpc()
+        );
+    });
+});
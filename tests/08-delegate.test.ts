@@ -0,0 +1,233 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import {
+    createAssociatedTokenAccount,
+    TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import {
+    findProgramStatePDA,
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findTokenVaultPDA,
+    findDriftUserPDA,
+    findDriftUserStatsPDA,
+    findDriftSpotMarketVaultPDA,
+    findDriftStatePDA
+} from "./utils/pda-gen";
+
+describe("array-protocol: Delegate", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    const DRIFT_PROGRAM_ID = new anchor.web3.PublicKey("DftNc7gwihkEEwQRpu4bV89N18xpNEuBVg7YkhTZZhVo");
+    const ROBOT_PUBKEY = new anchor.web3.PublicKey("Eo5ZcKDj3G3TxD2QWWaXA8Chg1DTtdZerXprq3tTFnPf");
+
+    // Mirrors DELEGATE_PERMISSION_* in state/user.rs
+    const DELEGATE_PERMISSION_DRIFT = 1 << 0;
+    const DELEGATE_PERMISSION_KAMINO = 1 << 1;
+    const DELEGATE_PERMISSION_ALL = DELEGATE_PERMISSION_DRIFT | DELEGATE_PERMISSION_KAMINO;
+
+    const keeper = anchor.web3.Keypair.generate();
+
+    let programStatePda: anchor.web3.PublicKey;
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+    let keeperTokenAccount: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+    const driftMarketIndex = 0;
+    const subAccountId = 0;
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    // Offset of `delegate` in the Drift `User` account
+    const DRIFT_USER_DELEGATE_OFFSET = 40;
+
+    // The user's existing Drift users, all of which set_delegate must update
+    const findDriftUsers = async () => {
+        const driftUsers: anchor.web3.AccountMeta[] = [];
+        for (let id = 0; id < 8; id++) {
+            const [driftUserPda] = findDriftUserPDA(userStatePda, id, DRIFT_PROGRAM_ID);
+            if (await provider.connection.getAccountInfo(driftUserPda)) {
+                driftUsers.push({ pubkey: driftUserPda, isSigner: false, isWritable: true });
+            }
+        }
+        return driftUsers;
+    };
+
+    const setDelegate = async (delegate: anchor.web3.PublicKey, permissions: number) =>
+        program.methods
+            .setDelegate(delegate, permissions)
+            .accounts({
+                signer: provider.wallet.publicKey,
+                userState: userStatePda,
+                driftUserStats: findDriftUserStatsPDA(userStatePda, DRIFT_PROGRAM_ID)[0],
+            })
+            .remainingAccounts(await findDriftUsers())
+            .rpc();
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+
+        const signature = await provider.connection.requestAirdrop(
+            keeper.publicKey,
+            anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+
+        const [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+
+        keeperTokenAccount = await createAssociatedTokenAccount(
+            provider.connection,
+            (provider.wallet as anchor.Wallet).payer,
+            vaultMint,
+            keeper.publicKey
+        );
+    });
+
+    after(async () => {
+        // Restore the default keeper for the remaining suites
+        await setDelegate(ROBOT_PUBKEY, DELEGATE_PERMISSION_ALL);
+    });
+
+    it("should let the authority set a delegate", async () => {
+        await setDelegate(keeper.publicKey, DELEGATE_PERMISSION_DRIFT);
+
+        const userStateData = await program.account.user.fetch(userStatePda);
+        expect(userStateData.delegate.toString()).to.equal(keeper.publicKey.toString());
+        expect(userStateData.delegatePermissions).to.equal(DELEGATE_PERMISSION_DRIFT);
+    });
+
+    it("should mirror the delegate onto the Drift users", async () => {
+        const driftUsers = await findDriftUsers();
+        for (const { pubkey } of driftUsers) {
+            const info = await provider.connection.getAccountInfo(pubkey);
+            const driftDelegate = new anchor.web3.PublicKey(
+                info.data.subarray(DRIFT_USER_DELEGATE_OFFSET, DRIFT_USER_DELEGATE_OFFSET + 32)
+            );
+            expect(driftDelegate.toString()).to.equal(keeper.publicKey.toString());
+        }
+
+        if (driftUsers.length > 0) {
+            await expectError(
+                program.methods
+                    .setDelegate(keeper.publicKey, DELEGATE_PERMISSION_DRIFT)
+                    .accounts({
+                        signer: provider.wallet.publicKey,
+                        userState: userStatePda,
+                        driftUserStats: findDriftUserStatsPDA(userStatePda, DRIFT_PROGRAM_ID)[0],
+                    })
+                    .remainingAccounts(driftUsers.slice(1))
+                    .rpc(),
+                "MissingProtocolAccounts"
+            );
+        }
+    });
+
+    it("should resync a Drift user's delegate", async () => {
+        const [driftUserPda] = findDriftUserPDA(userStatePda, subAccountId, DRIFT_PROGRAM_ID);
+        if (!(await provider.connection.getAccountInfo(driftUserPda))) {
+            console.warn("No Drift user for the provider. Skipping delegate resync test.");
+            return;
+        }
+
+        await program.methods
+            .updateDriftDelegate(subAccountId)
+            .accounts({
+                userState: userStatePda,
+                driftUser: driftUserPda,
+            })
+            .rpc();
+    });
+
+    it("should reject unknown permission bits", async () => {
+        await expectError(setDelegate(keeper.publicKey, 1 << 7), "InvalidDelegatePermissions");
+    });
+
+    it("should not let the delegate withdraw to a wallet", async () => {
+        await expectError(
+            program.methods
+                .withdrawSpl(vaultIndex, new anchor.BN(1))
+                .accounts({
+                    signer: keeper.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userState: userStatePda,
+                    userTokenAccount: keeperTokenAccount,
+                    state: programStatePda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    arraySigner: programSignerPda,
+                })
+                .signers([keeper])
+                .rpc(),
+            "UnauthorizedUser"
+        );
+    });
+
+    it("should not let the delegate replace itself", async () => {
+        await expectError(
+            program.methods
+                .setDelegate(keeper.publicKey, DELEGATE_PERMISSION_ALL)
+                .accounts({
+                    signer: keeper.publicKey,
+                    userState: userStatePda,
+                    driftUserStats: findDriftUserStatsPDA(userStatePda, DRIFT_PROGRAM_ID)[0],
+                })
+                .signers([keeper])
+                .rpc(),
+            "UnauthorizedUser"
+        );
+    });
+
+    it("should not let the delegate use a protocol it was not granted", async () => {
+        await setDelegate(keeper.publicKey, DELEGATE_PERMISSION_KAMINO);
+
+        const [driftStatePda] = findDriftStatePDA(DRIFT_PROGRAM_ID);
+        const [driftUserPda] = findDriftUserPDA(userStatePda, subAccountId, DRIFT_PROGRAM_ID);
+        const [driftUserStatsPda] = findDriftUserStatsPDA(userStatePda, DRIFT_PROGRAM_ID);
+        const [driftSpotMarketVaultPda] = findDriftSpotMarketVaultPDA(driftMarketIndex, DRIFT_PROGRAM_ID);
+
+        await expectError(
+            program.methods
+                .driftDeposit(vaultIndex, 0, driftMarketIndex, new anchor.BN(1))
+                .accounts({
+                    signer: keeper.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userState: userStatePda,
+                    driftState: driftStatePda,
+                    driftUser: driftUserPda,
+                    driftUserStats: driftUserStatsPda,
+                    spotMarketVault: driftSpotMarketVaultPda,
+                    arraySigner: programSignerPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([keeper])
+                .rpc(),
+            "UnauthorizedUser"
+        );
+    });
+
+    it("should let the authority revoke the delegate", async () => {
+        await setDelegate(anchor.web3.PublicKey.default, DELEGATE_PERMISSION_ALL);
+
+        const userStateData = await program.account.user.fetch(userStatePda);
+        expect(userStateData.delegate.toString()).to.equal(anchor.web3.PublicKey.default.toString());
+        expect(userStateData.delegatePermissions).to.equal(0, "Revoking should clear permissions");
+    });
+});
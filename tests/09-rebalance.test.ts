@@ -0,0 +1,79 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findUserStatePDA,
+    findTokenVaultPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Rebalance", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    let userStatePda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+    const driftMarketIndex = 0;
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    // Rebalance with none of the optional protocol accounts provided
+    const rebalance = (from: object, to: object, amount: number) =>
+        program.methods
+            .rebalance(vaultIndex, from as any, to as any, 0, driftMarketIndex, new anchor.BN(amount))
+            .accountsPartial({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: vaultMint,
+                userState: userStatePda,
+                driftState: null,
+                driftUser: null,
+                driftUserStats: null,
+                spotMarketVault: null,
+                driftSigner: null,
+                driftProgram: null,
+                obligation: null,
+                lendingMarket: null,
+                lendingMarketAuthority: null,
+                reserve: null,
+                reserveLiquiditySupply: null,
+                reserveCollateralMint: null,
+                reserveCollateralSupply: null,
+                instructionSysvarAccount: null,
+                klendProgram: null,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+    before(async () => {
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+
+        const [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+    });
+
+    it("should reject a rebalance into the same protocol", async () => {
+        await expectError(rebalance({ drift: {} }, { drift: {} }, 1), "InvalidRebalance");
+    });
+
+    it("should reject a rebalance without the destination protocol accounts", async () => {
+        await expectError(rebalance({ idle: {} }, { drift: {} }, 1), "MissingProtocolAccounts");
+    });
+
+    it("should reject a rebalance without the source protocol accounts", async () => {
+        await expectError(rebalance({ kamino: {} }, { idle: {} }, 1), "MissingProtocolAccounts");
+    });
+});
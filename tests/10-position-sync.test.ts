@@ -0,0 +1,110 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import {
+    findUserStatePDA,
+    findUserTokenVaultPDA,
+    findDriftUserPDA,
+    findDriftSpotMarketVaultPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Position Sync", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    const DRIFT_PROGRAM_ID = new anchor.web3.PublicKey("DftNc7gwihkEEwQRpu4bV89N18xpNEuBVg7YkhTZZhVo");
+
+    let userStatePda: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+    const usdcVaultIndex = 0; // USDC vault, deposited into Drift by drift-deposit.test.ts
+    const driftMarketIndex = 0; // USDC market index in Drift
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    before(async () => {
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+    });
+
+    it("should reject a kamino obligation not owned by klend", async () => {
+        // The user state is owned by array_protocol, not klend
+        await expectError(
+            program.methods
+                .syncKaminoPosition(vaultIndex)
+                .accounts({
+                    userState: userStatePda,
+                    obligation: userStatePda,
+                    reserve: userStatePda,
+                })
+                .rpc(),
+            "ConstraintOwner"
+        );
+    });
+
+    it("should reject a drift user not owned by drift", async () => {
+        await expectError(
+            program.methods
+                .syncDriftPosition(vaultIndex)
+                .accounts({
+                    userState: userStatePda,
+                    driftUser: userStatePda,
+                    spotMarket: userStatePda,
+                })
+                .rpc(),
+            "ConstraintOwner"
+        );
+    });
+
+    it("should book a synced drift value change into the user's share price", async function () {
+        const [userTokenVaultPda] = findUserTokenVaultPDA(userStatePda, usdcVaultIndex, program.programId);
+        const [driftUserPda] = findDriftUserPDA(userStatePda, 0, DRIFT_PROGRAM_ID);
+        const [spotMarketVaultPda] = findDriftSpotMarketVaultPDA(driftMarketIndex, DRIFT_PROGRAM_ID);
+        const marketIndexBytes = Buffer.alloc(2);
+        marketIndexBytes.writeUInt16LE(driftMarketIndex);
+        const [spotMarketPda] = anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("spot_market"), marketIndexBytes],
+            DRIFT_PROGRAM_ID
+        );
+
+        const findPosition = async () =>
+            (await program.account.user.fetch(userStatePda)).positions.find(
+                (pos) => pos.protocolVault.equals(spotMarketVaultPda) && pos.subAccountId === 0
+            );
+
+        const before = await findPosition();
+        if (!before || before.depositedAmount.isZero()) {
+            console.warn("No Drift position to sync. Skipping.");
+            this.skip();
+        }
+        const userVaultBefore = await program.account.userTokenVault.fetch(userTokenVaultPda);
+
+        await program.methods
+            .syncDriftPosition(usdcVaultIndex)
+            .accounts({
+                userState: userStatePda,
+                driftUser: driftUserPda,
+                spotMarket: spotMarketPda,
+            })
+            .rpc();
+
+        const after = await findPosition();
+        const userVaultAfter = await program.account.userTokenVault.fetch(userTokenVaultPda);
+
+        // The user's balance, and with it the value of each share, moves with the position value
+        const valueChange = after.value.sub(before.value);
+        expect(userVaultAfter.balance.sub(userVaultBefore.balance).toString()).to.equal(valueChange.toString());
+        expect(userVaultAfter.shares.toString()).to.equal(userVaultBefore.shares.toString());
+        expect(after.lastSyncedSlot.toNumber()).to.be.greaterThan(0);
+    });
+});
@@ -0,0 +1,120 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findProgramStatePDA,
+    findUserStatePDA,
+    findTokenVaultPDA,
+    findUserTokenVaultPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Fees", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    // Mirrors MAX_*_FEE_BPS in state/token_vault.rs
+    const MAX_PERFORMANCE_FEE_BPS = 3_000;
+    const MAX_MANAGEMENT_FEE_BPS = 500;
+
+    let programStatePda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let tokenVaultPda: anchor.web3.PublicKey;
+    let userTokenVaultPda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const setVaultFees = (performanceFeeBps: number, managementFeeBps: number) =>
+        program.methods
+            .setVaultFees(vaultIndex, performanceFeeBps, managementFeeBps)
+            .accounts({
+                admin: provider.wallet.publicKey,
+            })
+            .rpc();
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+        [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        [userTokenVaultPda] = findUserTokenVaultPDA(userStatePda, vaultIndex, program.programId);
+
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+    });
+
+    after(async () => {
+        // Leave the mock vault fee free for the remaining suites
+        await setVaultFees(0, 0);
+    });
+
+    it("should set the treasury", async () => {
+        await program.methods
+            .setTreasury(provider.wallet.publicKey)
+            .accounts({
+                admin: provider.wallet.publicKey,
+            })
+            .rpc();
+
+        const stateData = await program.account.programState.fetch(programStatePda);
+        expect(stateData.treasury.toString()).to.equal(provider.wallet.publicKey.toString());
+    });
+
+    it("should reject fees above the hard caps", async () => {
+        await expectError(setVaultFees(MAX_PERFORMANCE_FEE_BPS + 1, 0), "FeeTooHigh");
+        await expectError(setVaultFees(0, MAX_MANAGEMENT_FEE_BPS + 1), "FeeTooHigh");
+    });
+
+    it("should set vault fees within the caps", async () => {
+        await setVaultFees(MAX_PERFORMANCE_FEE_BPS, MAX_MANAGEMENT_FEE_BPS);
+
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        expect(vaultData.performanceFeeBps).to.equal(MAX_PERFORMANCE_FEE_BPS);
+        expect(vaultData.managementFeeBps).to.equal(MAX_MANAGEMENT_FEE_BPS);
+    });
+
+    it("should collect accrued fees to the treasury", async () => {
+        await program.methods
+            .collectFees(vaultIndex)
+            .accounts({
+                admin: provider.wallet.publicKey,
+                tokenVaultMint: vaultMint,
+                userState: userStatePda,
+                treasuryTokenAccount: getAssociatedTokenAddressSync(vaultMint, provider.wallet.publicKey),
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+        const userVaultData = await program.account.userTokenVault.fetch(userTokenVaultPda);
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        expect(userVaultData.accruedFees.toNumber()).to.equal(0, "Idle fees should all be collected");
+        expect(vaultData.accruedFees.toNumber()).to.equal(0);
+        expect(userVaultData.lastFeeAccrualTs.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("should reject resizing a user vault that already has the current layout", async () => {
+        await expectError(
+            program.methods
+                .resizeUserTokenVault()
+                .accounts({
+                    signer: provider.wallet.publicKey,
+                    userTokenVault: userTokenVaultPda,
+                })
+                .rpc(),
+            "AccountAlreadyResized"
+        );
+    });
+});
@@ -0,0 +1,93 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { findProgramStatePDA } from "./utils/pda-gen";
+
+describe("array-protocol: Admin Transfer", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    const newAdmin = anchor.web3.Keypair.generate();
+    const stranger = anchor.web3.Keypair.generate();
+
+    let programStatePda: anchor.web3.PublicKey;
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const proposeAdmin = (admin: anchor.web3.Keypair | null, candidate: anchor.web3.PublicKey) => {
+        const builder = program.methods
+            .proposeAdmin(candidate)
+            .accounts({
+                admin: admin ? admin.publicKey : provider.wallet.publicKey,
+            });
+        return admin ? builder.signers([admin]).rpc() : builder.rpc();
+    };
+
+    const acceptAdmin = (pendingAdmin: anchor.web3.Keypair | null) => {
+        const builder = program.methods
+            .acceptAdmin()
+            .accounts({
+                pendingAdmin: pendingAdmin ? pendingAdmin.publicKey : provider.wallet.publicKey,
+            });
+        return pendingAdmin ? builder.signers([pendingAdmin]).rpc() : builder.rpc();
+    };
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+
+        for (const kp of [newAdmin, stranger]) {
+            const signature = await provider.connection.requestAirdrop(
+                kp.publicKey,
+                anchor.web3.LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(signature);
+        }
+    });
+
+    it("should reject a proposal from a non-admin", async () => {
+        await expectError(proposeAdmin(stranger, stranger.publicKey), "UnauthorizedAdmin");
+    });
+
+    it("should propose a new admin", async () => {
+        await proposeAdmin(null, newAdmin.publicKey);
+
+        const stateData = await program.account.programState.fetch(programStatePda);
+        expect(stateData.admin.toString()).to.equal(provider.wallet.publicKey.toString(), "Admin should not change yet");
+        expect(stateData.pendingAdmin.toString()).to.equal(newAdmin.publicKey.toString());
+    });
+
+    it("should reject acceptance by anyone but the pending admin", async () => {
+        await expectError(acceptAdmin(stranger), "NotPendingAdmin");
+    });
+
+    it("should transfer admin once accepted", async () => {
+        await acceptAdmin(newAdmin);
+
+        const stateData = await program.account.programState.fetch(programStatePda);
+        expect(stateData.admin.toString()).to.equal(newAdmin.publicKey.toString());
+        expect(stateData.pendingAdmin.toString()).to.equal(anchor.web3.PublicKey.default.toString());
+    });
+
+    it("should reject the previous admin", async () => {
+        await expectError(proposeAdmin(null, provider.wallet.publicKey), "UnauthorizedAdmin");
+    });
+
+    it("should hand admin back to the provider wallet", async () => {
+        await proposeAdmin(newAdmin, provider.wallet.publicKey);
+        await acceptAdmin(null);
+
+        const stateData = await program.account.programState.fetch(programStatePda);
+        expect(stateData.admin.toString()).to.equal(provider.wallet.publicKey.toString());
+    });
+});
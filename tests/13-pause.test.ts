@@ -0,0 +1,166 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findProgramStatePDA,
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findTokenVaultPDA,
+    findDriftUserPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Pause", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    // Mirrors PAUSE_* in state/program_state.rs
+    const PAUSE_DEPOSITS = 1 << 0;
+    const PAUSE_WITHDRAWALS = 1 << 1;
+    const PAUSE_PROTOCOLS = 1 << 2;
+    const PAUSE_ALL = PAUSE_DEPOSITS | PAUSE_WITHDRAWALS | PAUSE_PROTOCOLS;
+
+    const stranger = anchor.web3.Keypair.generate();
+
+    let programStatePda: anchor.web3.PublicKey;
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let tokenVaultPda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+    let userTokenAccount: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const setPause = (paused: number) =>
+        program.methods
+            .setPause(paused)
+            .accounts({ admin: provider.wallet.publicKey })
+            .rpc();
+
+    const setVaultPause = (paused: number) =>
+        program.methods
+            .setVaultPause(vaultIndex, paused)
+            .accounts({ admin: provider.wallet.publicKey })
+            .rpc();
+
+    const depositSpl = (amount: number) =>
+        program.methods
+            .depositSpl(vaultIndex, new anchor.BN(amount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: vaultMint,
+                userTokenAccount: userTokenAccount,
+                userState: userStatePda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                arraySigner: programSignerPda,
+            })
+            .rpc();
+
+    const withdrawSpl = (amount: number) =>
+        program.methods
+            .withdrawSpl(vaultIndex, new anchor.BN(amount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: vaultMint,
+                userState: userStatePda,
+                userTokenAccount: userTokenAccount,
+                state: programStatePda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                arraySigner: programSignerPda,
+            })
+            .rpc();
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+        [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+        userTokenAccount = getAssociatedTokenAddressSync(vaultMint, provider.wallet.publicKey);
+
+        const signature = await provider.connection.requestAirdrop(
+            stranger.publicKey,
+            anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+    });
+
+    after(async () => {
+        await setPause(0);
+        await setVaultPause(0);
+    });
+
+    it("should only let the admin pause", async () => {
+        await expectError(
+            program.methods
+                .setPause(PAUSE_ALL)
+                .accounts({ admin: stranger.publicKey })
+                .signers([stranger])
+                .rpc(),
+            "UnauthorizedAdmin"
+        );
+    });
+
+    it("should reject unknown pause flags", async () => {
+        await expectError(setPause(1 << 7), "InvalidPauseFlags");
+    });
+
+    it("should block deposits while globally paused", async () => {
+        await setPause(PAUSE_ALL);
+
+        const stateData = await program.account.programState.fetch(programStatePda);
+        expect(stateData.paused).to.equal(PAUSE_ALL);
+
+        await expectError(depositSpl(1), "Paused");
+    });
+
+    it("should still allow withdrawals to the wallet while paused", async () => {
+        await withdrawSpl(1);
+    });
+
+    it("should block Drift delegate updates while protocols are paused", async () => {
+        const DRIFT_PROGRAM_ID = new anchor.web3.PublicKey("DftNc7gwihkEEwQRpu4bV89N18xpNEuBVg7YkhTZZhVo");
+        const [driftUserPda] = findDriftUserPDA(userStatePda, 0, DRIFT_PROGRAM_ID);
+
+        await expectError(
+            program.methods
+                .updateDriftDelegate(0)
+                .accounts({
+                    userState: userStatePda,
+                    driftUser: driftUserPda,
+                })
+                .rpc(),
+            "Paused"
+        );
+    });
+
+    it("should block deposits while the vault is paused", async () => {
+        await setPause(0);
+        await setVaultPause(PAUSE_DEPOSITS);
+
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        expect(vaultData.paused).to.equal(PAUSE_DEPOSITS);
+
+        await expectError(depositSpl(1), "VaultPaused");
+    });
+
+    it("should resume deposits once unpaused", async () => {
+        await setVaultPause(0);
+        await depositSpl(1);
+    });
+});
@@ -0,0 +1,108 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findTokenVaultPDA,
+    findUserTokenVaultPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Deposit Caps", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let tokenVaultPda: anchor.web3.PublicKey;
+    let userTokenVaultPda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+    let userTokenAccount: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const setDepositCaps = (maxTotalDeposits: anchor.BN | number, maxUserDeposits: anchor.BN | number) =>
+        program.methods
+            .setDepositCaps(vaultIndex, new anchor.BN(maxTotalDeposits), new anchor.BN(maxUserDeposits))
+            .accounts({ admin: provider.wallet.publicKey })
+            .rpc();
+
+    const depositSpl = (amount: number) =>
+        program.methods
+            .depositSpl(vaultIndex, new anchor.BN(amount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: vaultMint,
+                userTokenAccount: userTokenAccount,
+                userState: userStatePda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                arraySigner: programSignerPda,
+            })
+            .rpc();
+
+    before(async () => {
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+        [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        [userTokenVaultPda] = findUserTokenVaultPDA(userStatePda, vaultIndex, program.programId);
+
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+        userTokenAccount = getAssociatedTokenAddressSync(vaultMint, provider.wallet.publicKey);
+    });
+
+    after(async () => {
+        await setDepositCaps(0, 0);
+    });
+
+    it("should reject deposits past the per-user cap", async () => {
+        const userVaultData = await program.account.userTokenVault.fetch(userTokenVaultPda);
+        await setDepositCaps(0, userVaultData.depositedAmount);
+
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        expect(vaultData.maxUserDeposits.toString()).to.equal(userVaultData.depositedAmount.toString());
+
+        await expectError(depositSpl(1), "UserDepositCapExceeded");
+    });
+
+    it("should reject deposits past the vault cap", async () => {
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        await setDepositCaps(vaultData.balance, 0);
+
+        await expectError(depositSpl(1), "VaultDepositCapExceeded");
+    });
+
+    it("should accept deposits within the caps", async () => {
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        await setDepositCaps(vaultData.balance.addn(1), 0);
+
+        await depositSpl(1);
+    });
+
+    it("should reject resizing a vault that already has the current layout", async () => {
+        await expectError(
+            program.methods
+                .resizeTokenVault()
+                .accounts({
+                    signer: provider.wallet.publicKey,
+                    tokenVault: tokenVaultPda,
+                })
+                .rpc(),
+            "AccountAlreadyResized"
+        );
+    });
+});
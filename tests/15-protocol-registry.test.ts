@@ -0,0 +1,129 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { findProtocolRegistryPDA } from "./utils/pda-gen";
+
+describe("array-protocol: Protocol Registry", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    const KLEND_PROGRAM_ID = new anchor.web3.PublicKey("KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD");
+
+    const stranger = anchor.web3.Keypair.generate();
+
+    // Kamino market identified by its reserve and lending market
+    const market = {
+        protocol: KLEND_PROGRAM_ID,
+        market: anchor.web3.Keypair.generate().publicKey,
+        lendingMarket: anchor.web3.Keypair.generate().publicKey,
+        marketIndex: 0,
+        padding: [0, 0, 0, 0, 0, 0],
+    };
+
+    let registryPda: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const addMarket = () =>
+        program.methods
+            .addRegistryMarket(vaultIndex, market)
+            .accounts({ admin: provider.wallet.publicKey })
+            .rpc();
+
+    const removeMarket = () =>
+        program.methods
+            .removeRegistryMarket(vaultIndex, market)
+            .accounts({ admin: provider.wallet.publicKey })
+            .rpc();
+
+    before(async () => {
+        [registryPda] = findProtocolRegistryPDA(vaultIndex, program.programId);
+
+        const signature = await provider.connection.requestAirdrop(
+            stranger.publicKey,
+            anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+    });
+
+    it("should be created empty with the vault", async () => {
+        const registryData = await program.account.protocolRegistry.fetch(registryPda);
+        expect(registryData.tokenVaultIndex).to.equal(vaultIndex);
+        expect(registryData.len).to.equal(0);
+    });
+
+    it("should only let the admin register markets", async () => {
+        await expectError(
+            program.methods
+                .addRegistryMarket(vaultIndex, market)
+                .accounts({ admin: stranger.publicKey })
+                .signers([stranger])
+                .rpc(),
+            "UnauthorizedAdmin"
+        );
+    });
+
+    it("should register a market once", async () => {
+        await addMarket();
+
+        const registryData = await program.account.protocolRegistry.fetch(registryPda);
+        expect(registryData.len).to.equal(1);
+        expect(registryData.markets[0].market.toString()).to.equal(market.market.toString());
+
+        await expectError(addMarket(), "MarketAlreadyRegistered");
+    });
+
+    it("should remove a registered market", async () => {
+        await removeMarket();
+
+        const registryData = await program.account.protocolRegistry.fetch(registryPda);
+        expect(registryData.len).to.equal(0);
+
+        await expectError(removeMarket(), "MarketNotRegistered");
+    });
+
+    it("should ignore the padding of registered markets", async () => {
+        await program.methods
+            .addRegistryMarket(vaultIndex, { ...market, padding: [1, 2, 3, 4, 5, 6] })
+            .accounts({ admin: provider.wallet.publicKey })
+            .rpc();
+
+        const registryData = await program.account.protocolRegistry.fetch(registryPda);
+        expect(registryData.markets[0].padding).to.deep.equal([0, 0, 0, 0, 0, 0]);
+
+        await expectError(addMarket(), "MarketAlreadyRegistered");
+        await removeMarket();
+    });
+
+    it("should only create registries for existing vaults", async () => {
+        await expectError(
+            program.methods
+                .initProtocolRegistry(vaultIndex)
+                .accounts({ admin: stranger.publicKey })
+                .signers([stranger])
+                .rpc(),
+            "UnauthorizedAdmin"
+        );
+
+        await expectError(
+            program.methods
+                .initProtocolRegistry(999)
+                .accounts({ admin: provider.wallet.publicKey })
+                .rpc(),
+            "InvalidVaultIndex"
+        );
+    });
+});
@@ -0,0 +1,108 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findProgramStatePDA,
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findTokenVaultPDA,
+    findUserTokenVaultPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Events", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    let programStatePda: anchor.web3.PublicKey;
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let userTokenVaultPda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+    let userTokenAccount: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+
+    // Decodes the events a transaction emitted through self-CPI
+    const fetchEvents = async (signature: string) => {
+        await provider.connection.confirmTransaction(signature, "confirmed");
+        const tx = await provider.connection.getTransaction(signature, {
+            commitment: "confirmed",
+            maxSupportedTransactionVersion: 0,
+        });
+        const accountKeys = tx.transaction.message.getAccountKeys();
+
+        return tx.meta.innerInstructions
+            .flatMap((inner) => inner.instructions)
+            .filter((ix) => accountKeys.get(ix.programIdIndex).equals(program.programId))
+            .map((ix) => {
+                const data = anchor.utils.bytes.bs58.decode(ix.data);
+                // Skip the 8 byte event CPI tag
+                return program.coder.events.decode(
+                    anchor.utils.bytes.base64.encode(data.subarray(8))
+                );
+            })
+            .filter((event) => event !== null);
+    };
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+        [userTokenVaultPda] = findUserTokenVaultPDA(userStatePda, vaultIndex, program.programId);
+
+        const [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+        userTokenAccount = getAssociatedTokenAddressSync(vaultMint, provider.wallet.publicKey);
+    });
+
+    it("should emit a Deposited event on deposit_spl", async () => {
+        const amount = 10;
+        const signature = await program.methods
+            .depositSpl(vaultIndex, new anchor.BN(amount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: vaultMint,
+                userTokenAccount: userTokenAccount,
+                userState: userStatePda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                arraySigner: programSignerPda,
+            })
+            .rpc();
+
+        const events = await fetchEvents(signature);
+        expect(events).to.have.length(1);
+        expect(events[0].name).to.equal("deposited");
+
+        const userVaultData = await program.account.userTokenVault.fetch(userTokenVaultPda);
+        expect(events[0].data.user.toBase58()).to.equal(userStatePda.toBase58());
+        expect(events[0].data.vaultIndex).to.equal(vaultIndex);
+        expect(events[0].data.amount.toNumber()).to.equal(amount);
+        expect(events[0].data.postBalance.toString()).to.equal(userVaultData.balance.toString());
+    });
+
+    it("should emit a Withdrawn event on withdraw_spl", async () => {
+        const amount = 10;
+        const signature = await program.methods
+            .withdrawSpl(vaultIndex, new anchor.BN(amount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: vaultMint,
+                userState: userStatePda,
+                userTokenAccount: userTokenAccount,
+                state: programStatePda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                arraySigner: programSignerPda,
+            })
+            .rpc();
+
+        const events = await fetchEvents(signature);
+        expect(events).to.have.length(1);
+        expect(events[0].name).to.equal("withdrawn");
+        expect(events[0].data.amount.toNumber()).to.equal(amount);
+    });
+});
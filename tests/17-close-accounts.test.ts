@@ -0,0 +1,159 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findProgramStatePDA,
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findTokenVaultPDA,
+    findUserTokenVaultPDA,
+    findUserTokenVaultAccountPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Close Accounts", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    // A fresh user, so closing its accounts does not affect the other suites
+    const user = anchor.web3.Keypair.generate();
+
+    let programStatePda: anchor.web3.PublicKey;
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let userTokenVaultPda: anchor.web3.PublicKey;
+    let userTokenVaultAccountPda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    // close_user checks the user vault of every vault index is closed
+    const closeUser = async () => {
+        const stateData = await program.account.programState.fetch(programStatePda);
+        const userTokenVaults = Array.from({ length: stateData.tokenVaultCount }, (_, index) => ({
+            pubkey: findUserTokenVaultPDA(userStatePda, index, program.programId)[0],
+            isWritable: false,
+            isSigner: false,
+        }));
+
+        return program.methods
+            .closeUser()
+            .accounts({ authority: user.publicKey })
+            .remainingAccounts(userTokenVaults)
+            .signers([user])
+            .rpc();
+    };
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(user.publicKey, program.programId);
+        [userTokenVaultPda] = findUserTokenVaultPDA(userStatePda, vaultIndex, program.programId);
+        [userTokenVaultAccountPda] = findUserTokenVaultAccountPDA(userStatePda, vaultIndex, program.programId);
+
+        const [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+
+        const signature = await provider.connection.requestAirdrop(
+            user.publicKey,
+            anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+
+        await program.methods
+            .initUser()
+            .accounts({ signer: user.publicKey })
+            .signers([user])
+            .rpc();
+
+        await program.methods
+            .initUserTokenVault(vaultIndex)
+            .accounts({
+                signer: user.publicKey,
+                tokenVaultMint: vaultMint,
+                userState: userStatePda,
+                state: programStatePda,
+                arraySigner: programSignerPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([user])
+            .rpc();
+    });
+
+    it("should reject closing a user token vault that still holds funds", async () => {
+        // The provider's user still has deposits in the mock vault
+        await expectError(
+            program.methods
+                .closeUserTokenVault(vaultIndex)
+                .accounts({ authority: provider.wallet.publicKey, tokenProgram: TOKEN_PROGRAM_ID })
+                .rpc(),
+            "VaultNotEmpty"
+        );
+    });
+
+    it("should reject closing a user token vault from a foreign signer", async () => {
+        await expectError(
+            program.methods
+                .closeUserTokenVault(vaultIndex)
+                .accountsPartial({
+                    authority: provider.wallet.publicKey,
+                    userState: userStatePda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc(),
+            "ConstraintSeeds"
+        );
+    });
+
+    it("should reject closing a user whose user vaults are still open", async () => {
+        await expectError(closeUser(), "UserVaultOpen");
+    });
+
+    it("should reject closing a user without its user vaults", async () => {
+        await expectError(
+            program.methods
+                .closeUser()
+                .accounts({ authority: user.publicKey })
+                .signers([user])
+                .rpc(),
+            "AccountNotEnoughKeys"
+        );
+    });
+
+    it("should close an empty user token vault and return the rent", async () => {
+        const balanceBefore = await provider.connection.getBalance(user.publicKey);
+
+        await program.methods
+            .closeUserTokenVault(vaultIndex)
+            .accounts({ authority: user.publicKey, tokenProgram: TOKEN_PROGRAM_ID })
+            .signers([user])
+            .rpc();
+
+        expect(await provider.connection.getAccountInfo(userTokenVaultPda)).to.be.null;
+        expect(await provider.connection.getAccountInfo(userTokenVaultAccountPda)).to.be.null;
+        expect(await provider.connection.getBalance(user.publicKey)).to.be.greaterThan(balanceBefore);
+    });
+
+    it("should close the user account and return the rent", async () => {
+        const balanceBefore = await provider.connection.getBalance(user.publicKey);
+
+        await closeUser();
+
+        expect(await provider.connection.getAccountInfo(userStatePda)).to.be.null;
+        expect(await provider.connection.getBalance(user.publicKey)).to.be.greaterThan(balanceBefore);
+    });
+});
@@ -0,0 +1,72 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { findUserStatePDA } from "./utils/pda-gen";
+
+describe("array-protocol: User Positions", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    let userStatePda: anchor.web3.PublicKey;
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    before(async () => {
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+    });
+
+    it("should add position slots paid by the signer", async () => {
+        const before = await program.account.user.fetch(userStatePda);
+
+        await program.methods
+            .addUserPositions(2)
+            .accounts({
+                signer: provider.wallet.publicKey,
+                userState: userStatePda,
+            })
+            .rpc();
+
+        const after = await program.account.user.fetch(userStatePda);
+        expect(after.positions).to.have.lengthOf(before.positions.length + 2);
+        // Existing positions are kept as they were
+        expect(after.positions.slice(0, before.positions.length).map((pos) => pos.depositedAmount.toString()))
+            .to.deep.equal(before.positions.map((pos) => pos.depositedAmount.toString()));
+    });
+
+    it("should reject growing past the maximum number of slots", async () => {
+        await expectError(
+            program.methods
+                .addUserPositions(64)
+                .accounts({
+                    signer: provider.wallet.publicKey,
+                    userState: userStatePda,
+                })
+                .rpc(),
+            "TooManyPositions"
+        );
+    });
+
+    it("should reject migrating a user that is already migrated", async () => {
+        await expectError(
+            program.methods
+                .migrateUser()
+                .accounts({
+                    signer: provider.wallet.publicKey,
+                    userState: userStatePda,
+                })
+                .rpc(),
+            "NotLegacyUser"
+        );
+    });
+});
@@ -0,0 +1,159 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import {
+    createAssociatedTokenAccount,
+    createInitializeMintInstruction,
+    createInitializeNonTransferableMintInstruction,
+    createInitializeTransferFeeConfigInstruction,
+    ExtensionType,
+    getMintLen,
+    mintTo,
+    TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import {
+    findProgramStatePDA,
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findUserTokenVaultPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Token-2022", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+    const payer = (provider.wallet as anchor.Wallet).payer;
+
+    let programStatePda: anchor.web3.PublicKey;
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+
+    const transferFeeBps = 100;
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    // Creates a Token-2022 mint with the given extensions initialized by `extensionIxs`
+    const createMint2022 = async (
+        extensions: ExtensionType[],
+        extensionIxs: (mint: anchor.web3.PublicKey) => anchor.web3.TransactionInstruction[]
+    ) => {
+        const mint = anchor.web3.Keypair.generate();
+        const mintLen = getMintLen(extensions);
+        const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+        const tx = new anchor.web3.Transaction().add(
+            anchor.web3.SystemProgram.createAccount({
+                fromPubkey: payer.publicKey,
+                newAccountPubkey: mint.publicKey,
+                space: mintLen,
+                lamports,
+                programId: TOKEN_2022_PROGRAM_ID,
+            }),
+            ...extensionIxs(mint.publicKey),
+            createInitializeMintInstruction(mint.publicKey, 6, payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+        );
+        await provider.sendAndConfirm(tx, [mint]);
+        return mint.publicKey;
+    };
+
+    const initSupportedTokenVault = (mint: anchor.web3.PublicKey) =>
+        program.methods
+            .initSupportedTokenVault()
+            .accountsPartial({
+                admin: provider.wallet.publicKey,
+                state: programStatePda,
+                tokenVaultMint: mint,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+    });
+
+    it("should reject a mint with an unsupported extension", async () => {
+        const mint = await createMint2022([ExtensionType.NonTransferable], (mint) => [
+            createInitializeNonTransferableMintInstruction(mint, TOKEN_2022_PROGRAM_ID),
+        ]);
+
+        await expectError(initSupportedTokenVault(mint), "UnsupportedMintExtension");
+    });
+
+    it("should credit the amount received for a transfer-fee mint", async () => {
+        const mint = await createMint2022([ExtensionType.TransferFeeConfig], (mint) => [
+            createInitializeTransferFeeConfigInstruction(
+                mint,
+                payer.publicKey,
+                payer.publicKey,
+                transferFeeBps,
+                BigInt(1_000_000_000),
+                TOKEN_2022_PROGRAM_ID
+            ),
+        ]);
+
+        const { tokenVaultCount: vaultIndex } = await program.account.programState.fetch(programStatePda);
+        await initSupportedTokenVault(mint);
+
+        await program.methods
+            .initUserTokenVault(vaultIndex)
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: mint,
+                userState: userStatePda,
+                state: programStatePda,
+                arraySigner: programSignerPda,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+            })
+            .rpc();
+
+        const userTokenAccount = await createAssociatedTokenAccount(
+            provider.connection,
+            payer,
+            mint,
+            provider.wallet.publicKey,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+        );
+        await mintTo(
+            provider.connection,
+            payer,
+            mint,
+            userTokenAccount,
+            payer,
+            1_000_000,
+            [],
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+        );
+
+        const amount = 100_000;
+        await program.methods
+            .depositSpl(vaultIndex, new anchor.BN(amount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: mint,
+                userTokenAccount: userTokenAccount,
+                userState: userStatePda,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                arraySigner: programSignerPda,
+            })
+            .rpc();
+
+        const [userTokenVaultPda] = findUserTokenVaultPDA(userStatePda, vaultIndex, program.programId);
+        const userVaultData = await program.account.userTokenVault.fetch(userTokenVaultPda);
+        const fee = (amount * transferFeeBps) / 10_000;
+        expect(userVaultData.depositedAmount.toNumber()).to.equal(amount - fee);
+    });
+});
@@ -0,0 +1,155 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findProgramStatePDA,
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findTokenVaultPDA,
+    findUserTokenVaultPDA,
+    findWithdrawalRequestPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Queued Withdrawals", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    let programStatePda: anchor.web3.PublicKey;
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let tokenVaultPda: anchor.web3.PublicKey;
+    let userTokenVaultPda: anchor.web3.PublicKey;
+    let withdrawalRequestPda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+    let userTokenAccount: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+    const amount = 10;
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const setWithdrawalDelay = (delay: number) =>
+        program.methods
+            .setWithdrawalDelay(vaultIndex, new anchor.BN(delay))
+            .accounts({ admin: provider.wallet.publicKey })
+            .rpc();
+
+    const requestWithdraw = () =>
+        program.methods
+            .requestWithdraw(vaultIndex, new anchor.BN(amount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                userState: userStatePda,
+            })
+            .rpc();
+
+    const executeWithdraw = () =>
+        program.methods
+            .executeWithdraw(vaultIndex)
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: vaultMint,
+                userState: userStatePda,
+                userTokenAccount: userTokenAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+        [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        [userTokenVaultPda] = findUserTokenVaultPDA(userStatePda, vaultIndex, program.programId);
+        [withdrawalRequestPda] = findWithdrawalRequestPDA(userStatePda, vaultIndex, program.programId);
+
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+        userTokenAccount = getAssociatedTokenAddressSync(vaultMint, provider.wallet.publicKey);
+    });
+
+    after(async () => {
+        await setWithdrawalDelay(0);
+    });
+
+    it("should reject instant withdrawals while a delay is set", async () => {
+        await setWithdrawalDelay(3600);
+
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        expect(vaultData.withdrawalDelay.toNumber()).to.equal(3600);
+
+        await expectError(
+            program.methods
+                .withdrawSpl(vaultIndex, new anchor.BN(amount))
+                .accounts({
+                    signer: provider.wallet.publicKey,
+                    tokenVaultMint: vaultMint,
+                    userState: userStatePda,
+                    userTokenAccount: userTokenAccount,
+                    state: programStatePda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    arraySigner: programSignerPda,
+                })
+                .rpc(),
+            "WithdrawalDelayActive"
+        );
+    });
+
+    it("should lock the requested shares and principal", async () => {
+        const before = await program.account.userTokenVault.fetch(userTokenVaultPda);
+
+        await requestWithdraw();
+
+        const after = await program.account.userTokenVault.fetch(userTokenVaultPda);
+        const request = await program.account.withdrawalRequest.fetch(withdrawalRequestPda);
+        expect(request.amount.toNumber()).to.equal(amount);
+        expect(request.executableAt.sub(request.requestedAt).toNumber()).to.equal(3600);
+        expect(after.shares.toString()).to.equal(before.shares.sub(request.shares).toString());
+        expect(after.depositedAmount.toString()).to.equal(before.depositedAmount.sub(request.principal).toString());
+    });
+
+    it("should reject executing before the delay has passed", async () => {
+        await expectError(executeWithdraw(), "WithdrawalNotReady");
+    });
+
+    it("should return the locked shares on cancel", async () => {
+        const request = await program.account.withdrawalRequest.fetch(withdrawalRequestPda);
+        const before = await program.account.userTokenVault.fetch(userTokenVaultPda);
+
+        await program.methods
+            .cancelWithdraw(vaultIndex)
+            .accounts({
+                signer: provider.wallet.publicKey,
+                userState: userStatePda,
+            })
+            .rpc();
+
+        const after = await program.account.userTokenVault.fetch(userTokenVaultPda);
+        expect(after.shares.toString()).to.equal(before.shares.add(request.shares).toString());
+        expect(await provider.connection.getAccountInfo(withdrawalRequestPda)).to.be.null;
+    });
+
+    it("should execute a request once the delay has passed", async () => {
+        await setWithdrawalDelay(0);
+        await requestWithdraw();
+
+        const balanceBefore = await provider.connection.getTokenAccountBalance(userTokenAccount);
+        await executeWithdraw();
+        const balanceAfter = await provider.connection.getTokenAccountBalance(userTokenAccount);
+
+        expect(Number(balanceAfter.value.amount)).to.be.greaterThan(Number(balanceBefore.value.amount));
+        expect(await provider.connection.getAccountInfo(withdrawalRequestPda)).to.be.null;
+    });
+});
@@ -0,0 +1,100 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { NATIVE_MINT, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findProgramStatePDA,
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findUserTokenVaultPDA,
+    findUserTokenVaultAccountPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: SOL Vault", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    let programStatePda: anchor.web3.PublicKey;
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let userTokenVaultPda: anchor.web3.PublicKey;
+    let userTokenVaultAccountPda: anchor.web3.PublicKey;
+    let vaultIndex: number;
+
+    const depositAmount = anchor.web3.LAMPORTS_PER_SOL;
+
+    before(async () => {
+        [programStatePda] = findProgramStatePDA(program.programId);
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+
+        ({ tokenVaultCount: vaultIndex } = await program.account.programState.fetch(programStatePda));
+        [userTokenVaultPda] = findUserTokenVaultPDA(userStatePda, vaultIndex, program.programId);
+        [userTokenVaultAccountPda] = findUserTokenVaultAccountPDA(userStatePda, vaultIndex, program.programId);
+
+        await program.methods
+            .initSupportedTokenVault()
+            .accountsPartial({
+                admin: provider.wallet.publicKey,
+                state: programStatePda,
+                tokenVaultMint: NATIVE_MINT,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+        await program.methods
+            .initUserTokenVault(vaultIndex)
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: NATIVE_MINT,
+                userState: userStatePda,
+                state: programStatePda,
+                arraySigner: programSignerPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+    });
+
+    it("should wrap deposited SOL into the user vault", async () => {
+        await program.methods
+            .depositSol(vaultIndex, new anchor.BN(depositAmount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: NATIVE_MINT,
+                userState: userStatePda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+        const balance = await provider.connection.getTokenAccountBalance(userTokenVaultAccountPda);
+        expect(Number(balance.value.amount)).to.equal(depositAmount);
+
+        const userVaultData = await program.account.userTokenVault.fetch(userTokenVaultPda);
+        expect(userVaultData.depositedAmount.toNumber()).to.equal(depositAmount);
+    });
+
+    it("should unwrap withdrawn SOL to the signer", async () => {
+        const withdrawAmount = depositAmount / 2;
+        const lamportsBefore = await provider.connection.getBalance(provider.wallet.publicKey);
+
+        await program.methods
+            .withdrawSol(vaultIndex, new anchor.BN(withdrawAmount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: NATIVE_MINT,
+                userState: userStatePda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+        const lamportsAfter = await provider.connection.getBalance(provider.wallet.publicKey);
+        // Less the transaction fee
+        expect(lamportsAfter - lamportsBefore).to.be.greaterThan(withdrawAmount - 10_000);
+
+        const balance = await provider.connection.getTokenAccountBalance(userTokenVaultAccountPda);
+        expect(Number(balance.value.amount)).to.equal(depositAmount - withdrawAmount);
+    });
+});
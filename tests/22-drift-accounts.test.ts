@@ -0,0 +1,97 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findProgramSignerPDA,
+    findUserStatePDA,
+    findTokenVaultPDA,
+    findUserTokenVaultAccountPDA,
+    findDriftUserPDA,
+    findDriftUserStatsPDA,
+    findDriftSpotMarketVaultPDA,
+    findDriftStatePDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Drift Account Checks", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    const DRIFT_PROGRAM_ID = new anchor.web3.PublicKey("DftNc7gwihkEEwQRpu4bV89N18xpNEuBVg7YkhTZZhVo");
+
+    let programSignerPda: anchor.web3.PublicKey;
+    let userStatePda: anchor.web3.PublicKey;
+    let userTokenVaultAccountPda: anchor.web3.PublicKey;
+    let driftStatePda: anchor.web3.PublicKey;
+    let driftUserPda: anchor.web3.PublicKey;
+    let driftUserStatsPda: anchor.web3.PublicKey;
+    let driftSpotMarketVaultPda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+
+    const vaultIndex = 1; // Mock token vault from mock-vault.test.ts
+    const driftMarketIndex = 0;
+    const subAccountId = 0;
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const driftDeposit = (accounts: {
+        driftUser?: anchor.web3.PublicKey;
+        driftUserStats?: anchor.web3.PublicKey;
+        spotMarketVault?: anchor.web3.PublicKey;
+    }) =>
+        program.methods
+            .driftDeposit(vaultIndex, subAccountId, driftMarketIndex, new anchor.BN(1))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: vaultMint,
+                userState: userStatePda,
+                driftState: driftStatePda,
+                driftUser: accounts.driftUser ?? driftUserPda,
+                driftUserStats: accounts.driftUserStats ?? driftUserStatsPda,
+                spotMarketVault: accounts.spotMarketVault ?? driftSpotMarketVaultPda,
+                arraySigner: programSignerPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+    before(async () => {
+        [programSignerPda] = findProgramSignerPDA(program.programId);
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+        [userTokenVaultAccountPda] = findUserTokenVaultAccountPDA(userStatePda, vaultIndex, program.programId);
+
+        [driftStatePda] = findDriftStatePDA(DRIFT_PROGRAM_ID);
+        [driftUserPda] = findDriftUserPDA(userStatePda, subAccountId, DRIFT_PROGRAM_ID);
+        [driftUserStatsPda] = findDriftUserStatsPDA(userStatePda, DRIFT_PROGRAM_ID);
+        [driftSpotMarketVaultPda] = findDriftSpotMarketVaultPDA(driftMarketIndex, DRIFT_PROGRAM_ID);
+
+        const [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        const vaultData = await program.account.supportedTokenVault.fetch(tokenVaultPda);
+        vaultMint = vaultData.mint;
+    });
+
+    it("should reject a spot market vault of another market", async () => {
+        // Any token account that is not the market's vault
+        await expectError(driftDeposit({ spotMarketVault: userTokenVaultAccountPda }), "DriftMarketMismatch");
+    });
+
+    it("should reject the Drift user of another sub-account", async () => {
+        const [otherDriftUserPda] = findDriftUserPDA(userStatePda, subAccountId + 1, DRIFT_PROGRAM_ID);
+        await expectError(driftDeposit({ driftUser: otherDriftUserPda }), "InvalidDriftAccount");
+    });
+
+    it("should reject Drift user stats of another user", async () => {
+        const [otherUserStatsPda] = findDriftUserStatsPDA(provider.wallet.publicKey, DRIFT_PROGRAM_ID);
+        await expectError(driftDeposit({ driftUserStats: otherUserStatsPda }), "InvalidDriftAccount");
+    });
+});
@@ -0,0 +1,159 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import {
+    findUserStatePDA,
+    findDriftUserPDA,
+    findDriftUserStatsPDA,
+    findDriftSpotMarketVaultPDA,
+    findDriftStatePDA,
+} from "./utils/pda-gen";
+import { DriftClient } from "@drift-labs/sdk";
+
+describe("array-protocol: Drift Sub-Accounts", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    const DRIFT_PROGRAM_ID = new anchor.web3.PublicKey("DftNc7gwihkEEwQRpu4bV89N18xpNEuBVg7YkhTZZhVo");
+
+    let userStatePda: anchor.web3.PublicKey;
+    let driftStatePda: anchor.web3.PublicKey;
+    let driftUserStatsPda: anchor.web3.PublicKey;
+    let driftSpotMarketVaultPda: anchor.web3.PublicKey;
+
+    let driftClient: DriftClient;
+
+    const vaultIndex = 0; // USDC vault index
+    const driftMarketIndex = 0; // USDC market index in Drift
+    const mainSubAccountId = 0;
+    const otherSubAccountId = 1; // Created by the first deposit in drift-deposit.test.ts
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const findPosition = async (subAccountId: number) => {
+        const userData = await program.account.user.fetch(userStatePda);
+        return userData.positions.find(
+            (pos) => pos.protocolVault.equals(driftSpotMarketVaultPda) && pos.subAccountId === subAccountId
+        );
+    };
+
+    const transferDriftDeposit = (from: number, to: number, amount: number) =>
+        program.methods
+            .transferDriftDeposit(vaultIndex, from, to, driftMarketIndex, new anchor.BN(amount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                userState: userStatePda,
+                driftState: driftStatePda,
+                fromDriftUser: findDriftUserPDA(userStatePda, from, DRIFT_PROGRAM_ID)[0],
+                toDriftUser: findDriftUserPDA(userStatePda, to, DRIFT_PROGRAM_ID)[0],
+                driftUserStats: driftUserStatsPda,
+                spotMarketVault: driftSpotMarketVaultPda,
+            })
+            .remainingAccounts(
+                driftClient.getRemainingAccounts({
+                    userAccounts: [],
+                    writableSpotMarketIndexes: [driftMarketIndex],
+                    useMarketLastSlotCache: false,
+                })
+            )
+            .rpc();
+
+    const deleteDriftUser = (subAccountId: number) =>
+        program.methods
+            .deleteDriftUser(subAccountId)
+            .accounts({
+                authority: provider.wallet.publicKey,
+                driftState: driftStatePda,
+                driftUser: findDriftUserPDA(userStatePda, subAccountId, DRIFT_PROGRAM_ID)[0],
+                driftUserStats: driftUserStatsPda,
+            })
+            .rpc();
+
+    before(async () => {
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+        [driftStatePda] = findDriftStatePDA(DRIFT_PROGRAM_ID);
+        [driftUserStatsPda] = findDriftUserStatsPDA(userStatePda, DRIFT_PROGRAM_ID);
+        [driftSpotMarketVaultPda] = findDriftSpotMarketVaultPDA(driftMarketIndex, DRIFT_PROGRAM_ID);
+
+        driftClient = new DriftClient({
+            connection: program.provider.connection,
+            wallet: provider.wallet,
+            programID: DRIFT_PROGRAM_ID,
+        });
+        driftClient.authority = userStatePda;
+        await driftClient.subscribe();
+    });
+
+    after(async () => {
+        await driftClient.unsubscribe();
+    });
+
+    it("should reject a transfer within one sub-account", async () => {
+        await expectError(transferDriftDeposit(mainSubAccountId, mainSubAccountId, 1), "SameSubAccount");
+    });
+
+    it("should reject moving more than the source position holds", async function () {
+        const fromPosition = await findPosition(otherSubAccountId);
+        if (!fromPosition) {
+            console.warn("No position in the second sub-account. Skipping test.");
+            return;
+        }
+        const held = anchor.BN.max(fromPosition.value, fromPosition.depositedAmount);
+
+        await expectError(
+            transferDriftDeposit(otherSubAccountId, mainSubAccountId, held.toNumber() + 1),
+            "ExceedsPositionValue"
+        );
+    });
+
+    it("should move a deposit and its position between sub-accounts", async function () {
+        const fromPosition = await findPosition(otherSubAccountId);
+        if (!fromPosition || fromPosition.depositedAmount.isZero()) {
+            console.warn("No deposit in the second sub-account. Skipping transfer test.");
+            return;
+        }
+        const amount = fromPosition.depositedAmount.toNumber();
+        const toDepositedBefore = (await findPosition(mainSubAccountId))?.depositedAmount.toNumber() ?? 0;
+
+        await transferDriftDeposit(otherSubAccountId, mainSubAccountId, amount);
+
+        expect((await findPosition(otherSubAccountId)).depositedAmount.toNumber()).to.equal(0);
+        expect((await findPosition(mainSubAccountId)).depositedAmount.toNumber()).to.equal(
+            toDepositedBefore + amount
+        );
+    });
+
+    it("should refuse to delete a sub-account with an open position", async function () {
+        if (!(await findPosition(mainSubAccountId))) {
+            console.warn("No position in the main sub-account. Skipping test.");
+            return;
+        }
+        await expectError(deleteDriftUser(mainSubAccountId), "PositionOpen");
+    });
+
+    it("should delete an empty sub-account and return its rent", async function () {
+        const [driftUserPda] = findDriftUserPDA(userStatePda, otherSubAccountId, DRIFT_PROGRAM_ID);
+        if (!(await provider.connection.getAccountInfo(driftUserPda))) {
+            console.warn("No second Drift sub-account. Skipping delete test.");
+            return;
+        }
+        const balanceBefore = await provider.connection.getBalance(provider.wallet.publicKey);
+
+        await deleteDriftUser(otherSubAccountId);
+
+        expect(await provider.connection.getAccountInfo(driftUserPda)).to.be.null;
+        expect(await provider.connection.getBalance(provider.wallet.publicKey)).to.be.greaterThan(balanceBefore);
+        expect(await findPosition(otherSubAccountId)).to.be.undefined;
+    });
+});
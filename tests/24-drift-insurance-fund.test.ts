@@ -0,0 +1,179 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import {
+    findTokenVaultPDA,
+    findUserStatePDA,
+    findUserTokenVaultPDA,
+    findDriftUserStatsPDA,
+    findDriftSpotMarketVaultPDA,
+    findDriftStatePDA,
+    findDriftSignerPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Drift Insurance Fund", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    const DRIFT_PROGRAM_ID = new anchor.web3.PublicKey("DftNc7gwihkEEwQRpu4bV89N18xpNEuBVg7YkhTZZhVo");
+
+    let userStatePda: anchor.web3.PublicKey;
+    let userTokenVaultPda: anchor.web3.PublicKey;
+    let vaultMint: anchor.web3.PublicKey;
+    let driftStatePda: anchor.web3.PublicKey;
+    let driftSignerPda: anchor.web3.PublicKey;
+    let driftUserStatsPda: anchor.web3.PublicKey;
+    let driftSpotMarketVaultPda: anchor.web3.PublicKey;
+    let spotMarketPda: anchor.web3.PublicKey;
+    let insuranceFundVaultPda: anchor.web3.PublicKey;
+    let insuranceFundStakePda: anchor.web3.PublicKey;
+
+    const vaultIndex = 0; // USDC vault index
+    const mockVaultIndex = 1; // Mock token vault from mock-vault.test.ts
+    const driftMarketIndex = 0; // USDC market index in Drift
+    const stakeAmount = 1_000_000; // 1 USDC (6 decimals)
+
+    const marketIndexBytes = () => {
+        const bytes = Buffer.alloc(2);
+        bytes.writeUInt16LE(driftMarketIndex);
+        return bytes;
+    };
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const findPosition = async (kind: string) => {
+        const userData = await program.account.user.fetch(userStatePda);
+        return userData.positions.find(
+            (pos) => pos.protocolVault.equals(insuranceFundVaultPda) && kind in pos.kind
+        );
+    };
+
+    const addStake = (index: number, mint: anchor.web3.PublicKey) =>
+        program.methods
+            .addDriftInsuranceFundStake(index, driftMarketIndex, new anchor.BN(stakeAmount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                tokenVaultMint: mint,
+                userState: userStatePda,
+                driftState: driftStatePda,
+                spotMarket: spotMarketPda,
+                insuranceFundStake: insuranceFundStakePda,
+                driftUserStats: driftUserStatsPda,
+                spotMarketVault: driftSpotMarketVaultPda,
+                insuranceFundVault: insuranceFundVaultPda,
+                driftSigner: driftSignerPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .rpc();
+
+    before(async () => {
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+        [userTokenVaultPda] = findUserTokenVaultPDA(userStatePda, vaultIndex, program.programId);
+        [driftStatePda] = findDriftStatePDA(DRIFT_PROGRAM_ID);
+        [driftSignerPda] = findDriftSignerPDA(DRIFT_PROGRAM_ID);
+        [driftUserStatsPda] = findDriftUserStatsPDA(userStatePda, DRIFT_PROGRAM_ID);
+        [driftSpotMarketVaultPda] = findDriftSpotMarketVaultPDA(driftMarketIndex, DRIFT_PROGRAM_ID);
+        [spotMarketPda] = anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("spot_market"), marketIndexBytes()],
+            DRIFT_PROGRAM_ID
+        );
+        [insuranceFundVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("insurance_fund_vault"), marketIndexBytes()],
+            DRIFT_PROGRAM_ID
+        );
+        [insuranceFundStakePda] = anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("insurance_fund_stake"), userStatePda.toBuffer(), marketIndexBytes()],
+            DRIFT_PROGRAM_ID
+        );
+
+        const [tokenVaultPda] = findTokenVaultPDA(vaultIndex, program.programId);
+        vaultMint = (await program.account.supportedTokenVault.fetch(tokenVaultPda)).mint;
+    });
+
+    it("should register the insurance fund for the vault", async () => {
+        await program.methods
+            .addRegistryMarket(vaultIndex, {
+                protocol: DRIFT_PROGRAM_ID,
+                market: insuranceFundVaultPda,
+                lendingMarket: anchor.web3.PublicKey.default,
+                marketIndex: driftMarketIndex,
+                padding: [0, 0, 0, 0, 0, 0],
+            })
+            .accounts({ admin: provider.wallet.publicKey })
+            .rpc();
+    });
+
+    it("should initialize the insurance fund stake", async () => {
+        await program.methods
+            .initDriftInsuranceFundStake(driftMarketIndex)
+            .accounts({
+                signer: provider.wallet.publicKey,
+                userState: userStatePda,
+                driftState: driftStatePda,
+                spotMarket: spotMarketPda,
+                insuranceFundStake: insuranceFundStakePda,
+                driftUserStats: driftUserStatsPda,
+            })
+            .rpc();
+
+        expect(await provider.connection.getAccountInfo(insuranceFundStakePda)).to.not.be.null;
+    });
+
+    it("should reject staking from a vault the insurance fund is not registered for", async () => {
+        const [mockVaultPda] = findTokenVaultPDA(mockVaultIndex, program.programId);
+        const mockMint = (await program.account.supportedTokenVault.fetch(mockVaultPda)).mint;
+
+        await expectError(addStake(mockVaultIndex, mockMint), "MarketNotRegistered");
+    });
+
+    it("should stake vault funds as an insurance fund position", async function () {
+        const userVaultData = await program.account.userTokenVault.fetch(userTokenVaultPda);
+        if (userVaultData.depositedAmount.toNumber() < stakeAmount) {
+            console.warn("Not enough USDC in user vault. Skipping insurance fund tests.");
+            this.skip();
+        }
+
+        await addStake(vaultIndex, vaultMint);
+
+        const position = await findPosition("insuranceFundStake");
+        expect(position.depositedAmount.toNumber()).to.equal(stakeAmount);
+        expect(position.marketIndex).to.equal(driftMarketIndex);
+    });
+
+    it("should track a removal request as an unstaking position", async function () {
+        if (!(await findPosition("insuranceFundStake"))) {
+            this.skip();
+        }
+
+        await program.methods
+            .requestRemoveDriftInsuranceFundStake(vaultIndex, driftMarketIndex, new anchor.BN(stakeAmount))
+            .accounts({
+                signer: provider.wallet.publicKey,
+                userState: userStatePda,
+                spotMarket: spotMarketPda,
+                insuranceFundStake: insuranceFundStakePda,
+                driftUserStats: driftUserStatsPda,
+                insuranceFundVault: insuranceFundVaultPda,
+            })
+            .rpc();
+
+        const stake = await findPosition("insuranceFundStake");
+        expect(stake.depositedAmount.toNumber()).to.equal(0);
+
+        const unstake = await findPosition("insuranceFundUnstake");
+        expect(unstake.depositedAmount.toNumber()).to.equal(stakeAmount);
+        expect(unstake.lastSyncedSlot.toNumber()).to.be.greaterThan(0);
+    });
+});
@@ -0,0 +1,132 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { ArrayProtocol } from "../target/types/array_protocol";
+import { expect } from "chai";
+import {
+    findUserStatePDA,
+    findKaminoUserMetadataPDA,
+    findKaminoObligationPDA,
+    findKaminoLendingMarketAuthorityPDA,
+    findKaminoObligationFarmPDA,
+} from "./utils/pda-gen";
+
+describe("array-protocol: Kamino Account Checks", () => {
+    const provider = anchor.AnchorProvider.local();
+    anchor.setProvider(provider);
+
+    const program = anchor.workspace.ArrayProtocol as Program<ArrayProtocol>;
+
+    const KLEND_PROGRAM_ID = new anchor.web3.PublicKey("KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD");
+    const FARMS_PROGRAM_ID = new anchor.web3.PublicKey("FarmsPZpWu9i7Kky8tPN37rs2TpmMrAZrC7S7vJa91Hr");
+    // Kamino main market and its USDC reserve, cloned into the local validator
+    const LENDING_MARKET = new anchor.web3.PublicKey("7u3HeHxYDLhnCoErrtycNokbQYbWGzLs6JSDqGAv5PfF");
+    const USDC_RESERVE = new anchor.web3.PublicKey("D6q6wuQSrifJKZYpR1M8R4YawnLDtDsMmWM1NbBmgJ59");
+
+    let userStatePda: anchor.web3.PublicKey;
+    let userMetadataPda: anchor.web3.PublicKey;
+    let obligationPda: anchor.web3.PublicKey;
+
+    const obligationId = 0;
+
+    const expectError = async (promise: Promise<string>, code: string) => {
+        try {
+            await promise;
+            expect.fail("Instruction should have been rejected");
+        } catch (e) {
+            expect(e).to.be.instanceOf(anchor.AnchorError);
+            expect((e as anchor.AnchorError).error.errorCode.code).to.equal(code);
+        }
+    };
+
+    const initUserMetadata = (userMetadata: anchor.web3.PublicKey) =>
+        program.methods
+            .initKaminoUserMetadata()
+            .accountsPartial({
+                signer: provider.wallet.publicKey,
+                userState: userStatePda,
+                userMetadata,
+            })
+            .rpc();
+
+    const initObligation = (obligation: anchor.web3.PublicKey) =>
+        program.methods
+            .initKaminoObligation(obligationId)
+            .accountsPartial({
+                signer: provider.wallet.publicKey,
+                userState: userStatePda,
+                obligation,
+                lendingMarket: LENDING_MARKET,
+                seed1Account: anchor.web3.PublicKey.default,
+                seed2Account: anchor.web3.PublicKey.default,
+                ownerUserMetadata: userMetadataPda,
+            })
+            .rpc();
+
+    before(async function () {
+        if (!(await provider.connection.getAccountInfo(KLEND_PROGRAM_ID))) {
+            console.warn("klend is not deployed on this validator. Skipping Kamino account tests.");
+            this.skip();
+        }
+
+        [userStatePda] = findUserStatePDA(provider.wallet.publicKey, program.programId);
+        [userMetadataPda] = findKaminoUserMetadataPDA(userStatePda, KLEND_PROGRAM_ID);
+        [obligationPda] = findKaminoObligationPDA(userStatePda, LENDING_MARKET, obligationId, KLEND_PROGRAM_ID);
+    });
+
+    it("should reject user metadata of another owner", async () => {
+        const [otherUserMetadataPda] = findKaminoUserMetadataPDA(provider.wallet.publicKey, KLEND_PROGRAM_ID);
+        await expectError(initUserMetadata(otherUserMetadataPda), "InvalidKaminoAccount");
+    });
+
+    it("should initialize the user metadata at its derived address", async () => {
+        await initUserMetadata(userMetadataPda);
+
+        const accountInfo = await provider.connection.getAccountInfo(userMetadataPda);
+        expect(accountInfo.owner.toString()).to.equal(KLEND_PROGRAM_ID.toString());
+    });
+
+    it("should reject an obligation with another id", async () => {
+        const [otherObligationPda] = findKaminoObligationPDA(
+            userStatePda,
+            LENDING_MARKET,
+            obligationId + 1,
+            KLEND_PROGRAM_ID
+        );
+        await expectError(initObligation(otherObligationPda), "InvalidKaminoAccount");
+    });
+
+    it("should initialize the obligation at its derived address", async () => {
+        await initObligation(obligationPda);
+
+        const accountInfo = await provider.connection.getAccountInfo(obligationPda);
+        expect(accountInfo.owner.toString()).to.equal(KLEND_PROGRAM_ID.toString());
+    });
+
+    it("should reject an obligation farm of another obligation", async () => {
+        const reserveFarmState = anchor.web3.Keypair.generate().publicKey;
+        const [lendingMarketAuthority] = findKaminoLendingMarketAuthorityPDA(LENDING_MARKET, KLEND_PROGRAM_ID);
+        const [otherObligationFarm] = findKaminoObligationFarmPDA(
+            reserveFarmState,
+            userMetadataPda,
+            FARMS_PROGRAM_ID
+        );
+
+        await expectError(
+            program.methods
+                .initKaminoObligationFarms()
+                .accountsPartial({
+                    signer: provider.wallet.publicKey,
+                    userState: userStatePda,
+                    obligation: obligationPda,
+                    lendingMarketAuthority,
+                    reserve: USDC_RESERVE,
+                    reserveFarmState,
+                    obligationFarm: otherObligationFarm,
+                    lendingMarket: LENDING_MARKET,
+                    farmsProgram: FARMS_PROGRAM_ID,
+                })
+                .rpc(),
+            "InvalidKaminoAccount"
+        );
+    });
+});
@@ -40,6 +40,19 @@ export const findTokenVaultPDA = (
     );
 };
 
+export const findProtocolRegistryPDA = (
+    vaultIndex: number,
+    programId: anchor.web3.PublicKey
+): [anchor.web3.PublicKey, number] => {
+    const vaultIndexBytes = Buffer.alloc(2);
+    vaultIndexBytes.writeUInt16LE(vaultIndex);
+
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("protocol_registry"), vaultIndexBytes],
+        programId
+    );
+};
+
 export const findUserTokenVaultPDA = (
     userStatePda: anchor.web3.PublicKey,
     vaultIndex: number,
@@ -69,6 +82,20 @@ export const findUserTokenVaultAccountPDA = (
 };
 
 
+export const findWithdrawalRequestPDA = (
+    userStatePda: anchor.web3.PublicKey,
+    vaultIndex: number,
+    programId: anchor.web3.PublicKey
+): [anchor.web3.PublicKey, number] => {
+    const vaultIndexBytes = Buffer.alloc(2);
+    vaultIndexBytes.writeUInt16LE(vaultIndex);
+
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("withdrawal_request"), userStatePda.toBuffer(), vaultIndexBytes],
+        programId
+    );
+};
+
 export const findDriftUserPDA = (
     userKey: anchor.web3.PublicKey,
     subAccountId: number,
@@ -124,11 +151,53 @@ export const findDriftSignerPDA = (
     );
 };
 
+export const findKaminoUserMetadataPDA = (
+    ownerKey: anchor.web3.PublicKey,
+    programId: anchor.web3.PublicKey
+): [anchor.web3.PublicKey, number] => {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_meta"), ownerKey.toBuffer()],
+        programId
+    );
+};
 
+export const findKaminoObligationPDA = (
+    ownerKey: anchor.web3.PublicKey,
+    lendingMarket: anchor.web3.PublicKey,
+    obligationId: number,
+    programId: anchor.web3.PublicKey
+): [anchor.web3.PublicKey, number] => {
+    // Vanilla obligations use tag 0 and the default pubkey for both seed accounts
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [
+            Buffer.from([0]),
+            Buffer.from([obligationId]),
+            ownerKey.toBuffer(),
+            lendingMarket.toBuffer(),
+            anchor.web3.PublicKey.default.toBuffer(),
+            anchor.web3.PublicKey.default.toBuffer(),
+        ],
+        programId
+    );
+};
 
+export const findKaminoLendingMarketAuthorityPDA = (
+    lendingMarket: anchor.web3.PublicKey,
+    programId: anchor.web3.PublicKey
+): [anchor.web3.PublicKey, number] => {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("lma"), lendingMarket.toBuffer()],
+        programId
+    );
+};
 
-
-
-
-
-
+export const findKaminoObligationFarmPDA = (
+    farmState: anchor.web3.PublicKey,
+    obligation: anchor.web3.PublicKey,
+    farmsProgramId: anchor.web3.PublicKey
+): [anchor.web3.PublicKey, number] => {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user"), farmState.toBuffer(), obligation.toBuffer()],
+        farmsProgramId
+    );
+};